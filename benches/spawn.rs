@@ -0,0 +1,130 @@
+//! Spawn throughput with millions of tiny tasks.
+//!
+//! Run with `cargo bench --bench spawn`, optionally followed by `-- <tasks>`.
+
+use cadentis::{Runtime, RuntimeBuilder, Task};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+const DEFAULT_TASKS: usize = 1_000_000;
+const PRODUCERS: usize = 4;
+
+fn main() {
+    let tasks = std::env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with("--"))
+        .map(|arg| arg.parse().expect("task count must be a number"))
+        .unwrap_or(DEFAULT_TASKS);
+
+    let rt = RuntimeBuilder::new().build();
+    let workers = rt.handle().metrics().num_workers();
+
+    println!("{} tasks, {} workers", tasks, workers);
+
+    run("spawn from block_on, await each", tasks, || {
+        spawn_and_join(&rt, tasks)
+    });
+    run("spawn from tasks, detached", tasks, || {
+        spawn_from_tasks(&rt, tasks)
+    });
+    run(
+        &format!("spawn from {} threads, detached", PRODUCERS),
+        tasks,
+        || spawn_from_threads(&rt, tasks),
+    );
+    run("yield_now ping-pong", tasks, || yield_loop(&rt, tasks));
+}
+
+fn run(name: &str, tasks: usize, bench: impl FnOnce()) {
+    let start = Instant::now();
+    bench();
+    let elapsed = start.elapsed();
+
+    println!(
+        "{:<40} {:>10.2?} {:>12.0} tasks/s",
+        name,
+        elapsed,
+        tasks as f64 / elapsed.as_secs_f64()
+    );
+}
+
+fn spawn_and_join(rt: &Runtime, tasks: usize) {
+    rt.block_on(async {
+        let handles: Vec<_> = (0..tasks).map(|i| Task::spawn(async move { i })).collect();
+
+        for handle in handles {
+            handle.await;
+        }
+    });
+}
+
+fn spawn_from_tasks(rt: &Runtime, tasks: usize) {
+    let done = Arc::new(AtomicUsize::new(0));
+    let spawners = rt.handle().metrics().num_workers().max(1);
+
+    for spawner in 0..spawners {
+        let done = done.clone();
+        let count = tasks / spawners + usize::from(spawner < tasks % spawners);
+
+        rt.spawn(async move {
+            for _ in 0..count {
+                let done = done.clone();
+                Task::spawn(async move {
+                    done.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+        });
+    }
+
+    wait_until(&done, tasks);
+}
+
+fn spawn_from_threads(rt: &Runtime, tasks: usize) {
+    let done = Arc::new(AtomicUsize::new(0));
+
+    std::thread::scope(|scope| {
+        for producer in 0..PRODUCERS {
+            let handle = rt.handle().clone();
+            let done = done.clone();
+            let count = tasks / PRODUCERS + usize::from(producer < tasks % PRODUCERS);
+
+            scope.spawn(move || {
+                for _ in 0..count {
+                    let done = done.clone();
+                    handle.spawn(async move {
+                        done.fetch_add(1, Ordering::Relaxed);
+                    });
+                }
+            });
+        }
+    });
+
+    wait_until(&done, tasks);
+}
+
+/// Every task reschedules itself ten times, exercising the shared queue on
+/// every wakeup rather than on spawn only.
+fn yield_loop(rt: &Runtime, tasks: usize) {
+    let done = Arc::new(AtomicUsize::new(0));
+    let yielders = tasks / 10;
+
+    for _ in 0..yielders {
+        let done = done.clone();
+
+        rt.spawn(async move {
+            for _ in 0..10 {
+                cadentis::yield_now().await;
+            }
+            done.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    wait_until(&done, yielders);
+}
+
+fn wait_until(done: &AtomicUsize, count: usize) {
+    while done.load(Ordering::Relaxed) < count {
+        std::thread::sleep(Duration::from_micros(100));
+    }
+}
@@ -0,0 +1,330 @@
+//! `#[cadentis::main]` and `#[cadentis::test]`, re-exported by `cadentis`.
+//!
+//! Both turn an `async fn` into a plain function that builds a runtime and runs
+//! the body with `block_on`. The parser is hand written so that the crate has no
+//! dependencies.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Runs an `async fn main` on a Cadentis runtime.
+///
+/// ```ignore
+/// #[cadentis::main(worker_threads = 4, io)]
+/// async fn main() {
+///     // ...
+/// }
+/// ```
+///
+/// Accepted arguments:
+///
+/// - `flavor = "multi_thread"` (default) or `flavor = "current_thread"`, which
+///   runs every task on the calling thread;
+/// - `worker_threads = N`, only with the multi-threaded flavor;
+/// - `io` and `fs` to enable the reactor and the filesystem;
+/// - `time` to enable timers alone, which `io` and `fs` already do;
+/// - `start_paused = true` to start with a paused clock.
+#[proc_macro_attribute]
+pub fn main(args: TokenStream, item: TokenStream) -> TokenStream {
+    expand(Kind::Main, args, item)
+}
+
+/// Runs an `async fn` test on a fresh Cadentis runtime.
+///
+/// Accepts the same arguments as [`main`](macro@main):
+///
+/// ```ignore
+/// #[cadentis::test(time, start_paused = true)]
+/// async fn sleeps_without_waiting() {
+///     cadentis::time::sleep(std::time::Duration::from_secs(60)).await;
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn test(args: TokenStream, item: TokenStream) -> TokenStream {
+    expand(Kind::Test, args, item)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Main,
+    Test,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Flavor {
+    MultiThread,
+    CurrentThread,
+}
+
+struct Config {
+    flavor: Flavor,
+    worker_threads: Option<(usize, Span)>,
+    io: bool,
+    fs: bool,
+    time: bool,
+    start_paused: bool,
+}
+
+struct Error {
+    message: String,
+    span: Span,
+}
+
+impl Error {
+    fn new(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+
+    fn to_compile_error(&self) -> TokenStream {
+        let mut message = Literal::string(&self.message);
+        message.set_span(self.span);
+
+        let mut bang = Punct::new('!', Spacing::Alone);
+        bang.set_span(self.span);
+
+        let mut args = Group::new(Delimiter::Parenthesis, TokenTree::from(message).into());
+        args.set_span(self.span);
+
+        let mut semi = Punct::new(';', Spacing::Alone);
+        semi.set_span(self.span);
+
+        [
+            TokenTree::from(Ident::new("compile_error", self.span)),
+            bang.into(),
+            args.into(),
+            semi.into(),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+fn expand(kind: Kind, args: TokenStream, item: TokenStream) -> TokenStream {
+    let expanded = parse_args(args).and_then(|config| rewrite(kind, &config, item.clone()));
+
+    match expanded {
+        Ok(tokens) => tokens,
+        Err(error) => {
+            // Keep the original item so that uses of it still resolve.
+            let mut tokens = error.to_compile_error();
+            tokens.extend(item);
+            tokens
+        }
+    }
+}
+
+fn parse_args(args: TokenStream) -> Result<Config, Error> {
+    let mut config = Config {
+        flavor: Flavor::MultiThread,
+        worker_threads: None,
+        io: false,
+        fs: false,
+        time: false,
+        start_paused: false,
+    };
+
+    let mut tokens = args.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        let TokenTree::Ident(key) = token else {
+            return Err(Error::new(token.span(), "expected an argument name"));
+        };
+
+        let value = match tokens.peek() {
+            Some(TokenTree::Punct(p)) if p.as_char() == '=' => {
+                tokens.next();
+
+                match tokens.next() {
+                    Some(value) => Some(value),
+                    None => {
+                        return Err(Error::new(
+                            key.span(),
+                            format!("missing value for `{}`", key),
+                        ));
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        match key.to_string().as_str() {
+            "flavor" => {
+                config.flavor = match value.as_ref().and_then(string_value).as_deref() {
+                    Some("multi_thread") => Flavor::MultiThread,
+                    Some("current_thread") => Flavor::CurrentThread,
+                    _ => {
+                        return Err(Error::new(
+                            key.span(),
+                            "expected `flavor = \"multi_thread\"` or `flavor = \"current_thread\"`",
+                        ));
+                    }
+                };
+            }
+            "worker_threads" => {
+                let workers = value
+                    .as_ref()
+                    .and_then(|v| v.to_string().parse::<usize>().ok())
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| {
+                        Error::new(key.span(), "expected `worker_threads = N` with N > 0")
+                    })?;
+
+                config.worker_threads = Some((workers, key.span()));
+            }
+            "io" => config.io = flag_value(&key, value.as_ref())?,
+            "fs" => config.fs = flag_value(&key, value.as_ref())?,
+            "time" => config.time = flag_value(&key, value.as_ref())?,
+            "start_paused" => config.start_paused = flag_value(&key, value.as_ref())?,
+            _ => {
+                return Err(Error::new(
+                    key.span(),
+                    format!(
+                        "unknown argument `{}`, expected one of `flavor`, `worker_threads`, `io`, `fs`, `time`, `start_paused`",
+                        key
+                    ),
+                ));
+            }
+        }
+
+        match tokens.next() {
+            None => break,
+            Some(TokenTree::Punct(p)) if p.as_char() == ',' => {}
+            Some(token) => return Err(Error::new(token.span(), "expected `,`")),
+        }
+    }
+
+    if let Some((_, span)) = config.worker_threads
+        && config.flavor == Flavor::CurrentThread
+    {
+        return Err(Error::new(
+            span,
+            "`worker_threads` cannot be set with `flavor = \"current_thread\"`",
+        ));
+    }
+
+    Ok(config)
+}
+
+fn string_value(token: &TokenTree) -> Option<String> {
+    let TokenTree::Literal(literal) = token else {
+        return None;
+    };
+
+    let text = literal.to_string();
+
+    text.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .map(str::to_owned)
+}
+
+/// `io` on its own means `io = true`.
+fn flag_value(key: &Ident, value: Option<&TokenTree>) -> Result<bool, Error> {
+    match value {
+        None => Ok(true),
+        Some(TokenTree::Ident(v)) if v.to_string() == "true" => Ok(true),
+        Some(TokenTree::Ident(v)) if v.to_string() == "false" => Ok(false),
+        Some(v) => Err(Error::new(
+            v.span(),
+            format!("expected `{} = true` or `{} = false`", key, key),
+        )),
+    }
+}
+
+fn rewrite(kind: Kind, config: &Config, item: TokenStream) -> Result<TokenStream, Error> {
+    let mut tokens: Vec<TokenTree> = item.into_iter().collect();
+
+    let fn_index = tokens
+        .iter()
+        .position(|t| is_ident(t, "fn"))
+        .ok_or_else(|| Error::new(Span::call_site(), "expected a function"))?;
+
+    let async_index = tokens[..fn_index]
+        .iter()
+        .position(|t| is_ident(t, "async"))
+        .ok_or_else(|| {
+            Error::new(
+                tokens[fn_index].span(),
+                "the `async` keyword is missing from the function declaration",
+            )
+        })?;
+
+    if kind == Kind::Test {
+        let params = tokens[fn_index..].iter().find_map(|t| match t {
+            TokenTree::Group(g) if g.delimiter() == Delimiter::Parenthesis => Some(g),
+            _ => None,
+        });
+
+        if let Some(params) = params
+            && !params.stream().is_empty()
+        {
+            return Err(Error::new(
+                params.span(),
+                "test functions cannot take arguments",
+            ));
+        }
+    }
+
+    let body = match tokens.pop() {
+        Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => body,
+        _ => return Err(Error::new(Span::call_site(), "expected a function body")),
+    };
+
+    tokens.remove(async_index);
+
+    let mut output = TokenStream::new();
+
+    if kind == Kind::Test {
+        output.extend(parse("#[::core::prelude::v1::test]"));
+    }
+
+    output.extend(tokens);
+
+    let mut block = parse("let body = async move");
+    block.extend([TokenTree::Group(body)]);
+    block.extend(parse(&format!(";{}.block_on(body)", builder(config))));
+
+    output.extend([TokenTree::Group(Group::new(Delimiter::Brace, block))]);
+
+    Ok(output)
+}
+
+fn builder(config: &Config) -> String {
+    let mut builder = String::from("::cadentis::RuntimeBuilder::new()");
+
+    match (config.flavor, config.worker_threads) {
+        (Flavor::CurrentThread, _) => builder.push_str(".worker_threads(0)"),
+        (Flavor::MultiThread, Some((workers, _))) => {
+            builder.push_str(&format!(".worker_threads({})", workers))
+        }
+        (Flavor::MultiThread, None) => {}
+    }
+
+    if config.io {
+        builder.push_str(".enable_io()");
+    }
+
+    if config.fs {
+        builder.push_str(".enable_fs()");
+    }
+
+    if config.time {
+        builder.push_str(".enable_time()");
+    }
+
+    if config.start_paused {
+        builder.push_str(".start_paused(true)");
+    }
+
+    builder.push_str(".build()");
+    builder
+}
+
+fn is_ident(token: &TokenTree, name: &str) -> bool {
+    matches!(token, TokenTree::Ident(ident) if ident.to_string() == name)
+}
+
+fn parse(source: &str) -> TokenStream {
+    source.parse().expect("generated code is valid Rust")
+}
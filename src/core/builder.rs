@@ -1,8 +1,25 @@
-use crate::runtime::Runtime;
+use crate::core::task::Id;
+use crate::runtime::blocking::{BlockingPool, DEFAULT_KEEP_ALIVE, DEFAULT_MAX_BLOCKING_THREADS};
+use crate::runtime::executor::WorkerConfig;
+use crate::runtime::hooks::Hooks;
+use crate::runtime::watchdog::{Stall, Watchdog};
+use crate::runtime::{Features, Runtime};
+
+use std::sync::Arc;
+
+use std::time::Duration;
 
 pub struct RuntimeBuilder {
     enable_io: bool,
     enable_fs: bool,
+    enable_time: bool,
+    max_blocking_threads: usize,
+    thread_keep_alive: Duration,
+    workers: WorkerConfig,
+    max_tasks: Option<usize>,
+    seed: Option<u64>,
+    start_paused: bool,
+    hooks: Hooks,
 }
 
 impl Default for RuntimeBuilder {
@@ -16,21 +33,218 @@ impl RuntimeBuilder {
         Self {
             enable_io: false,
             enable_fs: false,
+            enable_time: false,
+            max_blocking_threads: DEFAULT_MAX_BLOCKING_THREADS,
+            thread_keep_alive: DEFAULT_KEEP_ALIVE,
+            workers: WorkerConfig::default(),
+            max_tasks: None,
+            seed: None,
+            start_paused: false,
+            hooks: Hooks::default(),
         }
     }
 
+    /// Enables sockets. Also enables timers, which most I/O code relies on.
     pub fn enable_io(mut self) -> Self {
         self.enable_io = true;
+        self.enable_time = true;
         self
     }
 
     pub fn enable_fs(mut self) -> Self {
         self.enable_fs = true;
         self.enable_io = true; // Filesystem support relies on reactor I/O for non-blocking operations.
+        self.enable_time = true;
+        self
+    }
+
+    /// Enables `time::sleep` and `time::timeout`, without the I/O poller.
+    pub fn enable_time(mut self) -> Self {
+        self.enable_time = true;
+        self
+    }
+
+    /// Enables I/O, the filesystem and timers.
+    pub fn enable_all(self) -> Self {
+        self.enable_fs().enable_time()
+    }
+
+    pub fn max_blocking_threads(mut self, max: usize) -> Self {
+        self.max_blocking_threads = max;
+        self
+    }
+
+    pub fn thread_keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.thread_keep_alive = keep_alive;
+        self
+    }
+
+    /// Number of worker threads, one per CPU by default. With `0`, every task runs
+    /// on the thread calling `block_on` and nothing makes progress outside of it.
+    pub fn worker_threads(mut self, workers: usize) -> Self {
+        self.workers.threads = Some(workers);
+        self
+    }
+
+    /// Makes the worker pool elastic, keeping at least `min` workers running.
+    /// Defaults to 1 once `max_workers` is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `min` is 0.
+    pub fn min_workers(mut self, min: usize) -> Self {
+        assert!(min > 0, "min_workers() must be at least 1");
+
+        self.workers.min = Some(min);
+        self
+    }
+
+    /// Makes the worker pool elastic, running at most `max` workers. Defaults to
+    /// `worker_threads`, or one per CPU. A worker is added when the shared queue
+    /// holds more tasks than running workers and none of them is idle.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `max` is 0.
+    pub fn max_workers(mut self, max: usize) -> Self {
+        assert!(max > 0, "max_workers() must be at least 1");
+
+        self.workers.max = Some(max);
+        self
+    }
+
+    /// How long a worker of an elastic pool stays idle before it exits. Tasks
+    /// pinned to it with `task::spawn_on` then run on any worker. Defaults to 10s.
+    pub fn worker_keep_alive(mut self, keep_alive: Duration) -> Self {
+        self.workers.keep_alive = keep_alive;
+        self
+    }
+
+    /// How long dropping the runtime waits for workers stuck in a poll before it
+    /// leaves them behind, as `Runtime::shutdown_timeout` does. Defaults to 10s.
+    pub fn drop_timeout(mut self, timeout: Duration) -> Self {
+        self.workers.drop_timeout = timeout;
+        self
+    }
+
+    /// Thread-per-core mode: starts one worker per entry of `cores`, pinned to that
+    /// core, and overrides `worker_threads` and the elastic bounds. Tasks spawned
+    /// from a worker stay on it and are never stolen, like tasks placed with
+    /// `task::spawn_on`; tasks spawned from other threads are still shared
+    /// between workers.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `cores` is empty.
+    pub fn thread_per_core(mut self, cores: impl IntoIterator<Item = usize>) -> Self {
+        let cores: Vec<usize> = cores.into_iter().collect();
+        assert!(
+            !cores.is_empty(),
+            "thread_per_core() needs at least one core"
+        );
+
+        self.workers.cores = Some(cores);
+        self
+    }
+
+    /// Caps the number of live tasks. `Handle::try_spawn` fails and
+    /// `Handle::spawn_wait` waits while the runtime is at the limit. Other ways of
+    /// spawning are never refused, but their tasks count towards the limit.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `max` is 0.
+    pub fn max_tasks(mut self, max: usize) -> Self {
+        assert!(max > 0, "max_tasks() must be at least 1");
+
+        self.max_tasks = Some(max);
+        self
+    }
+
+    /// Builds a deterministic simulation runtime instead of a multi-threaded one.
+    ///
+    /// All tasks run on the thread calling `block_on`, ready tasks are picked in an
+    /// order derived from `seed`, and timers use a virtual clock that jumps to the
+    /// next deadline whenever nothing is runnable. Running again with the same seed
+    /// replays the same schedule. Real sockets and blocking threads are still
+    /// driven by the OS and are not made deterministic.
+    pub fn simulation(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Starts the runtime with its clock paused, as if `time::pause()` was called first.
+    pub fn start_paused(mut self, paused: bool) -> Self {
+        self.start_paused = paused;
+        self
+    }
+
+    /// Called with the task ID whenever a task is spawned, on the spawning thread.
+    pub fn on_task_spawn<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(Id) + Send + Sync + 'static,
+    {
+        self.hooks.on_spawn = Some(Arc::new(hook));
+        self
+    }
+
+    /// Called on the polling thread right before a task is polled.
+    pub fn on_task_poll_start<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(Id) + Send + Sync + 'static,
+    {
+        self.hooks.on_poll_start = Some(Arc::new(hook));
+        self
+    }
+
+    /// Called on the polling thread right after a task is polled, with the poll duration.
+    pub fn on_task_poll_end<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(Id, Duration) + Send + Sync + 'static,
+    {
+        self.hooks.on_poll_end = Some(Arc::new(hook));
+        self
+    }
+
+    /// Called once when a task completes, panics, is aborted or is cancelled at shutdown.
+    pub fn on_task_terminate<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(Id) + Send + Sync + 'static,
+    {
+        self.hooks.on_terminate = Some(Arc::new(hook));
+        self
+    }
+
+    /// Reports every task poll that runs for at least `threshold`, and every worker
+    /// that has been stuck in a single poll for that long without returning to the
+    /// scheduler. `on_stall` runs on the polling thread for slow polls and on a
+    /// dedicated watchdog thread for stuck workers.
+    pub fn watchdog<F>(mut self, threshold: Duration, on_stall: F) -> Self
+    where
+        F: Fn(&Stall) + Send + Sync + 'static,
+    {
+        self.hooks.watchdog = Some(Watchdog {
+            threshold,
+            on_stall: Arc::new(on_stall),
+        });
         self
     }
 
     pub fn build(self) -> Runtime {
-        Runtime::with_features(self.enable_io, self.enable_fs)
+        let blocking = BlockingPool::new(self.max_blocking_threads, self.thread_keep_alive);
+
+        Runtime::with_features(
+            Features {
+                io_enabled: self.enable_io,
+                fs_enabled: self.enable_fs,
+                time_enabled: self.enable_time,
+            },
+            blocking,
+            self.workers,
+            self.max_tasks,
+            self.seed,
+            self.start_paused,
+            self.hooks,
+        )
     }
 }
@@ -1,118 +1,593 @@
+use crate::error::{ContextError, Error};
+use crate::runtime::SpawnError;
+use crate::runtime::budget;
+#[cfg(feature = "taskdump")]
+use crate::runtime::dump::{TaskInfo, TaskState, Trace};
 use crate::runtime::make_waker;
-use crate::runtime::workstealing::{CURRENT_INJECTOR, Injector};
+use crate::runtime::metrics;
+use crate::runtime::workstealing::{CURRENT_INJECTOR, Injector, current_worker};
+use crate::task::local::TaskLocals;
 
-use std::cell::UnsafeCell;
+use std::any::Any;
+use std::cell::{Cell, UnsafeCell};
+use std::fmt;
 use std::future::Future;
+use std::num::NonZeroU64;
+use std::panic::{AssertUnwindSafe, Location, catch_unwind};
 use std::pin::Pin;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll, Waker};
 
+pub(crate) const IDLE: u8 = 0;
+pub(crate) const SCHEDULED: u8 = 1;
+pub(crate) const RUNNING: u8 = 2;
+pub(crate) const NOTIFIED: u8 = 3;
+pub(crate) const COMPLETE: u8 = 4;
+pub(crate) const CANCELLED: u8 = 5;
+
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static CURRENT_TASK: Cell<Option<Id>> = const { Cell::new(None) };
+}
+
+/// Unique identifier of a task, stable for the lifetime of the process.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Id(NonZeroU64);
+
+impl Id {
+    fn next() -> Self {
+        let id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+
+        Self(NonZeroU64::new(id).expect("task ID space exhausted"))
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        self.0.get()
+    }
+}
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Returns the ID of the task currently being polled.
+///
+/// # Panics
+///
+/// Panics when called outside of a task.
+pub fn id() -> Id {
+    try_id().expect("task::id() called outside of a task")
+}
+
+/// Returns the ID of the task currently being polled, if any.
+pub fn try_id() -> Option<Id> {
+    CURRENT_TASK.with(Cell::get)
+}
+
+/// Scheduling class of a task. Higher classes are picked more often, but every
+/// class keeps making progress.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Background,
+}
+
+impl Priority {
+    pub(crate) const ALL: [Priority; 3] = [Priority::High, Priority::Normal, Priority::Background];
+
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Share of scheduling slots relative to the other classes.
+    pub(crate) const fn weight(self) -> i64 {
+        match self {
+            Priority::High => 8,
+            Priority::Normal => 4,
+            Priority::Background => 1,
+        }
+    }
+}
+
+/// Per-task options set through [`Builder`](crate::task::Builder).
+#[derive(Debug, Default)]
+pub(crate) struct TaskConfig {
+    pub(crate) name: Option<String>,
+    pub(crate) priority: Priority,
+    /// Worker whose local queue the task always runs from.
+    pub(crate) home: Option<usize>,
+    /// Spawn location when the task is not created at the caller's.
+    pub(crate) location: Option<&'static Location<'static>>,
+}
+
+type BoxedFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
 pub struct Task<T: Send + Sync + 'static> {
-    pub(crate) future: UnsafeCell<Pin<Box<dyn Future<Output = T> + Send>>>,
+    pub(crate) id: Id,
+    pub(crate) name: Option<String>,
+    pub(crate) priority: Priority,
+    pub(crate) home: Option<usize>,
+    pub(crate) location: &'static Location<'static>,
+    pub(crate) future: UnsafeCell<Option<BoxedFuture<T>>>,
+    pub(crate) locals: UnsafeCell<TaskLocals>,
 
-    pub(crate) result: UnsafeCell<Option<T>>,
-    pub(crate) completed: AtomicBool,
+    pub(crate) result: UnsafeCell<Option<Result<T, String>>>,
+    pub(crate) state: AtomicU8,
+    pub(crate) aborted: AtomicBool,
 
     pub(crate) injector: Arc<Injector>,
-    pub(crate) inqueue: AtomicBool,
 
     pub(crate) waiters: Mutex<Vec<Waker>>,
+
+    #[cfg(feature = "taskdump")]
+    pub(crate) trace: Trace,
 }
 
 unsafe impl<T> Sync for Task<T> where T: Send + Sync + 'static {}
 
 impl<T: Send + Sync + 'static> Task<T> {
-    pub(crate) fn new<F>(fut: F, injector: Arc<Injector>) -> Arc<Self>
+    #[track_caller]
+    pub(crate) fn new<F>(fut: F, injector: Arc<Injector>, config: TaskConfig) -> Arc<Self>
     where
         F: Future<Output = T> + Send + 'static,
     {
         Arc::new(Task {
-            future: UnsafeCell::new(Box::pin(fut)),
+            id: Id::next(),
+            name: config.name,
+            priority: config.priority,
+            home: config.home,
+            location: match config.location {
+                Some(location) => location,
+                None => Location::caller(),
+            },
+            future: UnsafeCell::new(Some(Box::pin(fut))),
+            locals: UnsafeCell::new(TaskLocals::inherited()),
             result: UnsafeCell::new(None),
+            state: AtomicU8::new(IDLE),
+            aborted: AtomicBool::new(false),
             injector,
-            inqueue: AtomicBool::new(false),
-            completed: AtomicBool::new(false),
             waiters: Mutex::new(Vec::new()),
+            #[cfg(feature = "taskdump")]
+            trace: Trace::new(),
         })
     }
 
     pub fn poll(self: &Arc<Self>) {
-        if self.completed.load(Ordering::Acquire) {
-            return;
+        let _ = self.poll_inner();
+    }
+
+    /// Polls the task once. Returns the panic message when the poll panicked.
+    fn poll_inner(self: &Arc<Self>) -> Result<(), String> {
+        if self
+            .state
+            .compare_exchange(SCHEDULED, RUNNING, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return Ok(());
         }
 
+        metrics::incr(&self.injector.metrics.polls);
+
+        #[cfg(feature = "taskdump")]
+        self.trace.record_poll();
+
         let waker = make_waker(self.clone());
         let mut context = Context::from_waker(&waker);
 
         let future = unsafe { &mut *self.future.get() };
+        let poll = match future.as_mut() {
+            Some(future) => {
+                let _locals = TaskLocals::enter(self.locals.get());
+                let prev = CURRENT_TASK.with(|current| current.replace(Some(self.id)));
+
+                let hooks = &self.injector.hooks;
+                hooks.poll_start(self.id);
+                let started = hooks.times_polls().then(std::time::Instant::now);
+
+                let poll = catch_unwind(AssertUnwindSafe(|| {
+                    budget::with_budget(|| future.as_mut().poll(&mut context))
+                }));
+
+                if let Some(started) = started {
+                    let elapsed = started.elapsed();
+                    hooks.poll_end(self.id, elapsed);
+
+                    if let Some(watchdog) = &hooks.watchdog {
+                        watchdog.check_poll(&**self, elapsed);
+                    }
+                }
+
+                CURRENT_TASK.with(|current| current.set(prev));
 
-        match future.as_mut().poll(&mut context) {
+                match poll {
+                    Ok(Poll::Ready(val)) => Poll::Ready(Ok(val)),
+                    Ok(Poll::Pending) => Poll::Pending,
+                    Err(payload) => Poll::Ready(Err(self.panic_message(payload))),
+                }
+            }
+            None => return Ok(()),
+        };
+
+        match poll {
             Poll::Pending => {
-                self.inqueue.store(false, Ordering::Release);
+                if self
+                    .state
+                    .compare_exchange(RUNNING, IDLE, Ordering::SeqCst, Ordering::SeqCst)
+                    .is_err()
+                {
+                    self.state.store(SCHEDULED, Ordering::SeqCst);
+
+                    if !self.cancel_if_aborted() {
+                        self.injector.reschedule(self.clone());
+                    }
+                } else {
+                    self.cancel_if_aborted();
+                }
             }
             Poll::Ready(val) => {
+                *future = None;
+
+                let panicked = val.as_ref().err().cloned();
+
                 unsafe {
                     *self.result.get() = Some(val);
                 }
 
-                self.completed.store(true, Ordering::Release);
+                self.state.store(COMPLETE, Ordering::Release);
 
-                self.injector.task_completed();
+                self.injector.task_completed(self.id);
 
                 let mut waiters = self.waiters.lock().unwrap();
                 waiters.drain(..).for_each(|w| w.wake());
+
+                if let Some(message) = panicked {
+                    return Err(message);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Cancels the task, or marks it so that the worker polling it cancels it
+    /// as soon as the current poll returns `Pending`.
+    pub(crate) fn abort(&self) {
+        self.aborted.store(true, Ordering::SeqCst);
+        self.cancel_if_aborted();
+    }
+
+    fn cancel_if_aborted(&self) -> bool {
+        if !self.aborted.load(Ordering::SeqCst) || !self.try_cancel() {
+            return false;
+        }
+
+        self.injector.task_completed(self.id);
+
+        let mut waiters = self.waiters.lock().unwrap();
+        waiters.drain(..).for_each(|w| w.wake());
+
+        true
+    }
+
+    /// Moves an idle or scheduled task to `CANCELLED` and drops its future.
+    fn try_cancel(&self) -> bool {
+        loop {
+            let state = self.state.load(Ordering::SeqCst);
+
+            if state != IDLE && state != SCHEDULED {
+                return false;
+            }
+
+            if self
+                .state
+                .compare_exchange(state, CANCELLED, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                break;
+            }
+        }
+
+        let future = unsafe { (*self.future.get()).take() };
+        drop(future);
+
+        true
+    }
+
+    fn panic_message(&self, payload: Box<dyn Any + Send>) -> String {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+
+        format!("{} panicked: {}", self.describe(), message)
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        matches!(self.state.load(Ordering::Acquire), COMPLETE | CANCELLED)
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        match &self.name {
+            Some(name) => format!("task {} ({})", self.id, name),
+            None => format!("task {}", self.id),
+        }
+    }
+
+    pub(crate) fn schedule(self: &Arc<Self>) {
+        loop {
+            match self.state.load(Ordering::Acquire) {
+                IDLE => {
+                    if self
+                        .state
+                        .compare_exchange(IDLE, SCHEDULED, Ordering::AcqRel, Ordering::Acquire)
+                        .is_ok()
+                    {
+                        self.injector.reschedule(self.clone());
+                        return;
+                    }
+                }
+                RUNNING => {
+                    if self
+                        .state
+                        .compare_exchange(RUNNING, NOTIFIED, Ordering::AcqRel, Ordering::Acquire)
+                        .is_ok()
+                    {
+                        return;
+                    }
+                }
+                _ => return,
             }
         }
     }
 
+    #[track_caller]
     pub fn spawn<F>(future: F) -> JoinHandle<T>
     where
         F: Future<Output = T> + Send + 'static,
     {
-        CURRENT_INJECTOR.with(|cell| {
-            let injector = cell
-                .borrow()
-                .as_ref()
-                .expect("Task::spawn() called outside of a runtime context")
-                .clone();
+        let injector = CURRENT_INJECTOR
+            .with(|cell| cell.borrow().clone())
+            .expect("Task::spawn() called outside of a runtime context");
 
-            let task = Task::new(future, injector.clone());
-            let r: Arc<dyn Runnable> = task.clone();
+        Task::spawn_with(future, injector, TaskConfig::default())
+    }
 
-            task.inqueue.store(true, Ordering::Release);
-            injector.push(r);
+    /// Like [`spawn`](Self::spawn), but fails outside of a runtime context, and
+    /// when the runtime is at its `RuntimeBuilder::max_tasks` limit.
+    #[track_caller]
+    pub fn try_spawn<F>(future: F) -> Result<JoinHandle<T>, Error>
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let injector = CURRENT_INJECTOR
+            .with(|cell| cell.borrow().clone())
+            .ok_or(ContextError::NoRuntime)?;
 
-            JoinHandle { task }
-        })
+        if !injector.try_admit() {
+            return Err(SpawnError.into());
+        }
+
+        Ok(Task::spawn_admitted(
+            future,
+            injector,
+            TaskConfig::default(),
+        ))
+    }
+
+    #[track_caller]
+    pub(crate) fn spawn_with<F>(
+        future: F,
+        injector: Arc<Injector>,
+        config: TaskConfig,
+    ) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        injector.admit();
+
+        Task::spawn_admitted(future, injector, config)
+    }
+
+    /// Spawns a task that already counts towards the runtime's live tasks.
+    #[track_caller]
+    pub(crate) fn spawn_admitted<F>(
+        future: F,
+        injector: Arc<Injector>,
+        mut config: TaskConfig,
+    ) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        // In thread-per-core mode, tasks spawned from a worker stay on it.
+        if config.home.is_none() && injector.thread_per_core {
+            config.home = current_worker(&injector);
+        }
+
+        let task = Task::new(future, injector.clone(), config);
+        let r: Arc<dyn Runnable> = task.clone();
+
+        task.state.store(SCHEDULED, Ordering::Release);
+        injector.push(r);
+
+        JoinHandle { task }
     }
 }
 
 pub(crate) trait Runnable: Send + Sync {
-    fn poll(self: Arc<Self>);
+    /// Returns the panic message, naming the task, when the poll panicked.
+    fn poll(self: Arc<Self>) -> Result<(), String>;
+
+    fn id(&self) -> Id;
+
+    fn priority(&self) -> Priority;
+
+    fn home(&self) -> Option<usize>;
+
+    fn name(&self) -> Option<&str>;
+
+    fn location(&self) -> &'static Location<'static>;
+
+    /// Cancels an idle or scheduled task and wakes its awaiters. The caller
+    /// gives back the task's slot.
+    fn cancel(&self) -> bool;
+
+    fn is_running(&self) -> bool;
+
+    #[cfg(feature = "taskdump")]
+    fn info(&self) -> TaskInfo;
 }
 
 impl<T: Send + Sync + 'static> Runnable for Task<T> {
-    fn poll(self: Arc<Self>) {
-        Task::poll(&self);
+    fn poll(self: Arc<Self>) -> Result<(), String> {
+        self.poll_inner()
+    }
+
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    fn home(&self) -> Option<usize> {
+        self.home
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    fn cancel(&self) -> bool {
+        if !self.try_cancel() {
+            return false;
+        }
+
+        let mut waiters = self.waiters.lock().unwrap();
+        waiters.drain(..).for_each(|w| w.wake());
+
+        true
+    }
+
+    fn is_running(&self) -> bool {
+        matches!(self.state.load(Ordering::Acquire), RUNNING | NOTIFIED)
+    }
+
+    #[cfg(feature = "taskdump")]
+    fn info(&self) -> TaskInfo {
+        let state = match self.state.load(Ordering::Acquire) {
+            SCHEDULED => TaskState::Scheduled,
+            RUNNING | NOTIFIED => TaskState::Running,
+            _ => TaskState::Idle,
+        };
+
+        self.trace
+            .info(self.id, self.name.as_deref(), state, self.location)
     }
 }
 
 pub struct JoinHandle<T: Send + Sync + 'static> {
-    task: Arc<Task<T>>,
+    pub(crate) task: Arc<Task<T>>,
 }
 
+impl<T: Send + Sync> JoinHandle<T> {
+    pub fn id(&self) -> Id {
+        self.task.id
+    }
+
+    /// Cancels the task. A task that is being polled is cancelled once it yields.
+    /// Awaiting an aborted task panics.
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    pub(crate) fn take_result(&self) -> T {
+        match self.try_take_result() {
+            Ok(value) => value,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    pub(crate) fn try_take_result(&self) -> Result<T, JoinError> {
+        match unsafe { (*self.task.result.get()).take() } {
+            Some(Ok(value)) => Ok(value),
+            Some(Err(message)) => Err(JoinError {
+                id: self.task.id,
+                panicked: true,
+                message,
+            }),
+            None => Err(JoinError {
+                id: self.task.id,
+                panicked: false,
+                message: format!("{} was cancelled", self.task.describe()),
+            }),
+        }
+    }
+}
+
+/// Why a task finished without an output: it panicked, or it was aborted or
+/// cancelled at shutdown.
+#[derive(Clone, Debug)]
+pub struct JoinError {
+    id: Id,
+    panicked: bool,
+    message: String,
+}
+
+impl JoinError {
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Whether the task was aborted or cancelled at shutdown.
+    pub fn is_cancelled(&self) -> bool {
+        !self.panicked
+    }
+
+    pub fn is_panic(&self) -> bool {
+        self.panicked
+    }
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for JoinError {}
+
 impl<T: Send + Sync> Future for JoinHandle<T> {
     type Output = T;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if self.task.completed.load(Ordering::Acquire) {
-            let result = unsafe { (*self.task.result.get()).take() }.unwrap();
-
-            return Poll::Ready(result);
+        if self.task.is_finished() {
+            return Poll::Ready(self.take_result());
         }
 
         let mut waiters = self.task.waiters.lock().unwrap();
+
+        if self.task.is_finished() {
+            drop(waiters);
+
+            return Poll::Ready(self.take_result());
+        }
+
         waiters.push(cx.waker().clone());
 
         Poll::Pending
@@ -0,0 +1,89 @@
+use crate::runtime::SpawnError;
+
+use std::fmt;
+use std::io;
+
+/// What the current runtime context lacks for an operation to run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ContextError {
+    /// Called outside of a task, `block_on` or `Handle::enter`.
+    NoRuntime,
+    /// The runtime was built without `RuntimeBuilder::enable_io()`.
+    IoDisabled,
+    /// The runtime was built without `RuntimeBuilder::enable_fs()`.
+    FsDisabled,
+    /// The runtime was built without `RuntimeBuilder::enable_time()`.
+    TimeDisabled,
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextError::NoRuntime => f.write_str(
+                "no runtime context. Call from within Runtime::block_on or a task, or use Handle::enter().",
+            ),
+            ContextError::IoDisabled => {
+                f.write_str("I/O support not enabled. Use RuntimeBuilder::enable_io().")
+            }
+            ContextError::FsDisabled => {
+                f.write_str("filesystem support not enabled. Use RuntimeBuilder::enable_fs().")
+            }
+            ContextError::TimeDisabled => {
+                f.write_str("timer support not enabled. Use RuntimeBuilder::enable_time().")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ContextError {}
+
+/// Returned by the `try_*` counterparts of operations that otherwise panic
+/// when the runtime context does not support them.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    Context(ContextError),
+    /// The runtime is at its task limit.
+    Spawn(SpawnError),
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Context(error) => error.fmt(f),
+            Error::Spawn(error) => error.fmt(f),
+            Error::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+// Transparent: the message and source are those of the wrapped error.
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Context(error) => error.source(),
+            Error::Spawn(error) => error.source(),
+            Error::Io(error) => error.source(),
+        }
+    }
+}
+
+impl From<ContextError> for Error {
+    fn from(error: ContextError) -> Self {
+        Error::Context(error)
+    }
+}
+
+impl From<SpawnError> for Error {
+    fn from(error: SpawnError) -> Self {
+        Error::Spawn(error)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
@@ -1,7 +1,8 @@
+use crate::Error;
 use crate::reactor::core::ReactorHandle;
 use crate::reactor::event::Event;
 use crate::reactor::future::{ReadFuture, WriteFuture};
-use crate::runtime::context::current_reactor_fs;
+use crate::runtime::context::{current_reactor_fs, try_current_reactor_fs};
 
 use libc::{O_CREAT, O_RDONLY, O_TRUNC, O_WRONLY, close, open};
 use std::ffi::CString;
@@ -17,6 +18,14 @@ impl File {
         Self::open_with_flags(path, O_RDONLY).await
     }
 
+    /// Like [`open`](Self::open), but fails instead of panicking when the runtime
+    /// was built without `enable_fs()` or there is no runtime.
+    pub async fn try_open(path: &str) -> Result<Self, Error> {
+        let reactor = try_current_reactor_fs()?;
+
+        Ok(Self::open_with_reactor(path, O_RDONLY, reactor).await?)
+    }
+
     pub async fn create(path: &str) -> io::Result<Self> {
         Self::open_with_flags(path, O_CREAT | O_WRONLY | O_TRUNC).await
     }
@@ -1,12 +1,28 @@
 mod core;
+mod error;
 mod reactor;
 mod runtime;
 
 pub mod fs;
 pub mod net;
+pub mod task;
 pub mod time;
 pub mod tools;
 
+pub use cadentis_macros::{main, test};
 pub use core::builder::RuntimeBuilder;
 pub use core::task::{JoinHandle, Task};
+pub use error::{ContextError, Error};
 pub use runtime::yield_now::yield_now;
+pub use runtime::{
+    EnterGuard, Handle, Runtime, RuntimeMetrics, ShutdownReport, SpawnError, SpawnWait, Stall,
+    StallKind,
+};
+#[cfg(feature = "taskdump")]
+pub use runtime::{TaskDump, TaskInfo, TaskState};
+
+/// Internals exposed for the integration tests only.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::runtime::queue::{BLOCK_CAP, SegQueue};
+}
@@ -1,6 +1,7 @@
 use crate::net::utils::sockaddr_to_socketaddr;
 use crate::reactor::core::ReactorHandle;
 use crate::reactor::event::Event;
+use crate::runtime::budget::poll_proceed;
 
 use libc::{EAGAIN, EWOULDBLOCK, accept, sockaddr, sockaddr_in, socklen_t};
 use std::future::Future;
@@ -8,12 +9,11 @@ use std::io;
 use std::mem;
 use std::net::SocketAddr;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, ready};
 
 pub struct AcceptFuture {
     listen_file_descriptor: i32,
     reactor: ReactorHandle,
-    registered: bool,
 }
 
 impl AcceptFuture {
@@ -21,7 +21,6 @@ impl AcceptFuture {
         Self {
             listen_file_descriptor,
             reactor,
-            registered: false,
         }
     }
 }
@@ -29,7 +28,9 @@ impl AcceptFuture {
 impl Future for AcceptFuture {
     type Output = io::Result<(i32, SocketAddr)>;
 
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let coop = ready!(poll_proceed(cx));
+
         let mut addr: sockaddr_in = unsafe { mem::zeroed() };
         let mut addr_len: socklen_t = mem::size_of::<sockaddr_in>() as socklen_t;
 
@@ -44,23 +45,22 @@ impl Future for AcceptFuture {
         if client_fd >= 0 {
             Event::set_nonblocking(client_fd);
             let socket_addr = sockaddr_to_socketaddr(&addr);
+            coop.made_progress();
             return Poll::Ready(Ok((client_fd, socket_addr)));
         }
 
         let error = unsafe { *libc::__error() };
 
         if error == EAGAIN || error == EWOULDBLOCK {
-            if !self.registered {
-                self.reactor
-                    .lock()
-                    .unwrap()
-                    .register_read(self.listen_file_descriptor, cx.waker().clone());
-                self.registered = true;
-            }
+            self.reactor
+                .lock()
+                .unwrap()
+                .register_read(self.listen_file_descriptor, cx.waker().clone());
 
             return Poll::Pending;
         }
 
+        coop.made_progress();
         Poll::Ready(Err(io::Error::last_os_error()))
     }
 }
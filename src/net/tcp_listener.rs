@@ -1,9 +1,10 @@
+use crate::Error;
 use crate::net::future::AcceptFuture;
 use crate::net::tcp_stream::TcpStream;
 use crate::net::utils::sockaddr_to_socketaddr;
 use crate::reactor::core::ReactorHandle;
 use crate::reactor::event::Event;
-use crate::runtime::context::current_reactor_io;
+use crate::runtime::context::{current_reactor_io, try_current_reactor_io};
 
 use libc::{AF_INET, SOCK_STREAM, bind, close, getsockname, listen, sockaddr, sockaddr_in, socket};
 use std::io;
@@ -20,6 +21,14 @@ impl TcpListener {
         Self::bind_with_reactor(address, current_reactor_io()).await
     }
 
+    /// Like [`bind`](Self::bind), but fails instead of panicking when the runtime
+    /// was built without `enable_io()` or there is no runtime.
+    pub async fn try_bind(address: &str) -> Result<Self, Error> {
+        let reactor = try_current_reactor_io()?;
+
+        Ok(Self::bind_with_reactor(address, reactor).await?)
+    }
+
     pub async fn bind_with_reactor(address: &str, reactor: ReactorHandle) -> io::Result<Self> {
         let addr = crate::net::utils::parse_sockaddr(address)?;
         let file_descriptor = unsafe { socket(AF_INET, SOCK_STREAM, 0) };
@@ -2,17 +2,61 @@ use crate::reactor::event::Event;
 use crate::reactor::io::{Connection, ConnectionState};
 use crate::reactor::socket::accept_client;
 
-use libc::{
-    EAGAIN, EVFILT_READ, EVFILT_TIMER, EVFILT_WRITE, EWOULDBLOCK, close, kqueue, read, write,
-};
+use libc::{EAGAIN, EVFILT_READ, EVFILT_WRITE, EWOULDBLOCK, close, kqueue, read, write};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::task::Waker;
 use std::time::Duration;
 
 pub type ReactorHandle = Arc<Mutex<Reactor>>;
 
+/// Ident of the user event used to interrupt `Reactor::wait`.
+const UNPARK_IDENT: usize = 0;
+
+/// The reactor's kqueues. They are closed once the reactor and every unparker
+/// are gone, as detached workers may still wait on them after shutdown.
+struct Queues {
+    /// I/O interest, polled without blocking by any thread.
+    io: i32,
+    /// What the parked driver waits on: the unpark event, and `io` having
+    /// events. Polling `io` never sees the unpark, so it is never consumed by
+    /// a thread other than the one it is meant for.
+    park: i32,
+}
+
+impl Queues {
+    fn new() -> Self {
+        let io = unsafe { kqueue() };
+        let park = unsafe { kqueue() };
+
+        Event::register_user(park, UNPARK_IDENT);
+        Event::new(io as usize, EVFILT_READ).register(park);
+
+        Self { io, park }
+    }
+}
+
+impl Drop for Queues {
+    fn drop(&mut self) {
+        unsafe {
+            close(self.park);
+            close(self.io);
+        }
+    }
+}
+
+/// Interrupts a thread blocked in [`Reactor::wait`].
+#[derive(Clone)]
+pub(crate) struct ReactorUnpark {
+    queues: Arc<Queues>,
+}
+
+impl ReactorUnpark {
+    pub(crate) fn unpark(&self) {
+        Event::trigger(self.queues.park, UNPARK_IDENT);
+    }
+}
+
 pub(crate) enum Entry {
     #[allow(unused)]
     Listener,
@@ -21,13 +65,27 @@ pub(crate) enum Entry {
 }
 
 pub struct Reactor {
-    queue: i32,
+    queues: Arc<Queues>,
     events: [Event; 64],
     n_events: i32,
     registry: HashMap<i32, Entry>,
-    timers: HashMap<usize, (Waker, Arc<AtomicBool>)>,
-    next_timer_id: usize,
     wakers: Vec<Waker>,
+    turns: u64,
+    events_total: u64,
+}
+
+/// Events collected by [`Reactor::wait`].
+pub(crate) struct ReadyEvents {
+    events: [Event; 64],
+    len: i32,
+}
+
+/// Counters exposed through `RuntimeMetrics`.
+pub(crate) struct ReactorStats {
+    pub(crate) turns: u64,
+    pub(crate) events: u64,
+    pub(crate) events_last_turn: usize,
+    pub(crate) registered_fds: usize,
 }
 
 unsafe impl Send for Reactor {}
@@ -38,65 +96,110 @@ const OUT_MAX_BYTES: usize = 8 * 1024 * 1024;
 impl Reactor {
     pub(crate) fn new() -> Self {
         Self {
-            queue: unsafe { kqueue() },
+            queues: Arc::new(Queues::new()),
             events: [Event::EMPTY; 64],
             n_events: 0,
             registry: HashMap::new(),
-            timers: HashMap::new(),
-            next_timer_id: 1,
             wakers: Vec::new(),
+            turns: 0,
+            events_total: 0,
+        }
+    }
+
+    pub(crate) fn unparker(&self) -> ReactorUnpark {
+        ReactorUnpark {
+            queues: self.queues.clone(),
         }
     }
 
+    /// Blocks until an I/O event arrives, the reactor is unparked or `timeout`
+    /// elapses. The lock is not held while waiting, so that other threads can
+    /// keep registering interest. Pass the result to [`Reactor::dispatch`].
+    pub(crate) fn wait(reactor: &Mutex<Reactor>, timeout: Option<Duration>) -> ReadyEvents {
+        let queues = reactor.lock().unwrap().queues.clone();
+
+        let mut events = [Event::EMPTY; 64];
+        Event::wait(queues.park, &mut events, timeout);
+
+        // Another thread may have polled the events that woke us up already.
+        let len = Event::try_wait(queues.io, &mut events);
+
+        ReadyEvents { events, len }
+    }
+
+    /// Handles events returned by [`Reactor::wait`] and wakes their tasks.
+    pub(crate) fn dispatch(&mut self, ready: ReadyEvents) {
+        self.turns += 1;
+
+        if ready.len > 0 {
+            self.events = ready.events;
+            self.n_events = ready.len;
+            self.events_total += ready.len as u64;
+            self.handle_events();
+        }
+
+        self.wake_ready();
+    }
+
+    /// Wakes `waker` once `file_descriptor` is readable. The interest is
+    /// dropped when it fires, as the filter is level-triggered and would keep
+    /// reporting unread data; a future still not ready registers again.
     pub(crate) fn register_read(&mut self, file_descriptor: i32, waker: Waker) {
-        let event = Event::new(file_descriptor as usize, EVFILT_READ, None);
-        event.register(self.queue);
+        let event = Event::new(file_descriptor as usize, EVFILT_READ);
+        event.register(self.queues.io);
 
         self.registry.insert(file_descriptor, Entry::Waiting(waker));
     }
 
+    /// Wakes `waker` once `file_descriptor` is writable, like `register_read`.
     pub(crate) fn register_write(&mut self, file_descriptor: i32, waker: Waker) {
-        let event = Event::new(file_descriptor as usize, EVFILT_WRITE, None);
-        event.register(self.queue);
+        let event = Event::new(file_descriptor as usize, EVFILT_WRITE);
+        event.register(self.queues.io);
 
         self.registry.insert(file_descriptor, Entry::Waiting(waker));
     }
 
-    pub(crate) fn register_timer(&mut self, duration: Duration, waker: Waker) {
-        self.register_timer_with_callback(duration, waker, Arc::new(AtomicBool::new(false)));
-    }
-
-    pub(crate) fn register_timer_with_callback(
-        &mut self,
-        duration: Duration,
-        waker: Waker,
-        expired: Arc<AtomicBool>,
-    ) {
-        let milliseconds = duration.as_millis().clamp(0, isize::MAX as u128) as isize;
-        let id = self.next_timer_id;
-        self.next_timer_id = self.next_timer_id.wrapping_add(1).max(1);
-
-        let event = Event::new(id, EVFILT_TIMER, Some(milliseconds));
-        event.register(self.queue);
+    pub(crate) fn shutdown(&mut self) {
+        for (file_descriptor, entry) in std::mem::take(&mut self.registry) {
+            match entry {
+                Entry::Client(_) => self.cleanup(file_descriptor),
+                _ => {
+                    Event::unregister(self.queues.io, file_descriptor as usize, EVFILT_READ);
+                    Event::unregister(self.queues.io, file_descriptor as usize, EVFILT_WRITE);
+                }
+            }
+        }
 
-        self.timers.insert(id, (waker, expired));
+        self.wakers.clear();
+        self.n_events = 0;
     }
 
     fn unregister_write(&self, file_descriptor: i32) {
-        Event::unregister(self.queue, file_descriptor as usize, EVFILT_WRITE);
+        Event::unregister(self.queues.io, file_descriptor as usize, EVFILT_WRITE);
     }
 
     pub(crate) fn poll_events(&mut self) {
-        let n_events = Event::try_wait(self.queue, &mut self.events);
+        let n_events = Event::try_wait(self.queues.io, &mut self.events);
+        self.turns += 1;
 
         if n_events <= 0 {
             return;
         }
 
         self.n_events = n_events;
+        self.events_total += n_events as u64;
         self.handle_events();
     }
 
+    pub(crate) fn stats(&self) -> ReactorStats {
+        ReactorStats {
+            turns: self.turns,
+            events: self.events_total,
+            events_last_turn: self.n_events.max(0) as usize,
+            registered_fds: self.registry.len(),
+        }
+    }
+
     pub(crate) fn wake_ready(&mut self) {
         for waker in self.wakers.drain(..) {
             waker.wake();
@@ -112,19 +215,19 @@ impl Reactor {
                 EVFILT_READ
                     if matches!(self.registry.get(&(file_descriptor)), Some(Entry::Listener)) =>
                 {
-                    accept_client(self.queue, &mut self.registry, file_descriptor);
+                    accept_client(self.queues.io, &mut self.registry, file_descriptor);
                 }
 
                 EVFILT_READ => {
-                    let mut entry = match self.registry.remove(&file_descriptor) {
-                        Some(entry) => entry,
-                        None => continue,
+                    let Some(mut entry) = self.registry.remove(&file_descriptor) else {
+                        Event::unregister(self.queues.io, file_descriptor as usize, filter);
+                        continue;
                     };
 
                     match &mut entry {
                         Entry::Waiting(waker) => {
+                            Event::unregister(self.queues.io, file_descriptor as usize, filter);
                             self.wakers.push(waker.clone());
-                            self.registry.insert(file_descriptor, entry);
                             continue;
                         }
                         Entry::Client(connection)
@@ -148,15 +251,15 @@ impl Reactor {
                 }
 
                 EVFILT_WRITE => {
-                    let mut entry = match self.registry.remove(&file_descriptor) {
-                        Some(entry) => entry,
-                        None => continue,
+                    let Some(mut entry) = self.registry.remove(&file_descriptor) else {
+                        Event::unregister(self.queues.io, file_descriptor as usize, filter);
+                        continue;
                     };
 
                     match &mut entry {
                         Entry::Waiting(waker) => {
+                            Event::unregister(self.queues.io, file_descriptor as usize, filter);
                             self.wakers.push(waker.clone());
-                            self.registry.insert(file_descriptor, entry);
                             continue;
                         }
                         Entry::Client(connection)
@@ -179,15 +282,6 @@ impl Reactor {
                     }
                 }
 
-                EVFILT_TIMER => {
-                    let timer_id = event.get_ident();
-
-                    if let Some((waker, expired)) = self.timers.remove(&timer_id) {
-                        expired.store(true, Ordering::Release);
-                        self.wakers.push(waker);
-                    }
-                }
-
                 _ => {}
             }
         }
@@ -254,8 +348,8 @@ impl Reactor {
     }
 
     fn cleanup(&self, file_descriptor: i32) {
-        Event::unregister(self.queue, file_descriptor as usize, EVFILT_READ);
-        Event::unregister(self.queue, file_descriptor as usize, EVFILT_WRITE);
+        Event::unregister(self.queues.io, file_descriptor as usize, EVFILT_READ);
+        Event::unregister(self.queues.io, file_descriptor as usize, EVFILT_WRITE);
         unsafe { close(file_descriptor) };
     }
 }
@@ -1,5 +1,9 @@
-use libc::{EV_ADD, EV_DELETE, EV_ENABLE, F_GETFL, F_SETFL, O_NONBLOCK, fcntl, kevent};
+use libc::{
+    EV_ADD, EV_CLEAR, EV_DELETE, EV_ENABLE, EVFILT_USER, F_GETFL, F_SETFL, NOTE_TRIGGER,
+    O_NONBLOCK, fcntl, kevent,
+};
 use std::ptr;
+use std::time::Duration;
 
 pub(crate) struct Event(kevent);
 
@@ -13,15 +17,13 @@ impl Event {
         udata: ptr::null_mut(),
     });
 
-    pub(crate) fn new(ident: usize, filter: i16, timer_milliseconds: Option<isize>) -> Self {
-        let data = timer_milliseconds.unwrap_or(0);
-
+    pub(crate) fn new(ident: usize, filter: i16) -> Self {
         Self(kevent {
             ident,
             filter,
             flags: EV_ADD | EV_ENABLE,
             fflags: 0,
-            data,
+            data: 0,
             udata: ptr::null_mut(),
         })
     }
@@ -41,12 +43,28 @@ impl Event {
     }
 
     pub(crate) fn unregister(queue: i32, ident: usize, filter: i16) {
-        let mut event = Self::new(ident, filter, None);
+        let mut event = Self::new(ident, filter);
         event.0.flags = EV_DELETE;
 
         event.register(queue);
     }
 
+    /// Registers a user event that `trigger` fires and the next wait clears.
+    pub(crate) fn register_user(queue: i32, ident: usize) {
+        let mut event = Self::new(ident, EVFILT_USER);
+        event.0.flags |= EV_CLEAR;
+
+        event.register(queue);
+    }
+
+    pub(crate) fn trigger(queue: i32, ident: usize) {
+        let mut event = Self::new(ident, EVFILT_USER);
+        event.0.flags = 0;
+        event.0.fflags = NOTE_TRIGGER;
+
+        event.register(queue);
+    }
+
     pub(crate) fn try_wait(queue: i32, events: &mut [Event; 64]) -> i32 {
         let timespec = libc::timespec {
             tv_sec: 0,
@@ -65,6 +83,27 @@ impl Event {
         }
     }
 
+    /// Blocks until at least one event is ready or `timeout` elapses.
+    pub(crate) fn wait(queue: i32, events: &mut [Event; 64], timeout: Option<Duration>) -> i32 {
+        let timespec = timeout.map(|timeout| libc::timespec {
+            tv_sec: timeout.as_secs() as libc::time_t,
+            tv_nsec: timeout.subsec_nanos() as libc::c_long,
+        });
+
+        unsafe {
+            kevent(
+                queue,
+                ptr::null(),
+                0,
+                events.as_mut_ptr() as *mut kevent,
+                events.len() as i32,
+                timespec
+                    .as_ref()
+                    .map_or(ptr::null(), |t| t as *const libc::timespec),
+            )
+        }
+    }
+
     pub(crate) fn set_nonblocking(file_descriptor: i32) {
         let flags = unsafe { fcntl(file_descriptor, F_GETFL) };
 
@@ -1,9 +1,10 @@
 use crate::reactor::core::ReactorHandle;
+use crate::runtime::budget::poll_proceed;
 
 use std::future::Future;
 use std::io;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, ready};
 
 use libc::{EAGAIN, EWOULDBLOCK, read, write};
 
@@ -11,7 +12,6 @@ pub struct ReadFuture<'a> {
     file_descriptor: i32,
     buffer: &'a mut [u8],
     reactor: ReactorHandle,
-    registered: bool,
 }
 
 impl<'a> ReadFuture<'a> {
@@ -20,7 +20,6 @@ impl<'a> ReadFuture<'a> {
             file_descriptor,
             buffer,
             reactor,
-            registered: false,
         }
     }
 }
@@ -29,6 +28,8 @@ impl<'a> Future for ReadFuture<'a> {
     type Output = io::Result<usize>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let coop = ready!(poll_proceed(cx));
+
         let this = self.as_mut().get_mut();
 
         let result = unsafe {
@@ -39,28 +40,23 @@ impl<'a> Future for ReadFuture<'a> {
             )
         };
 
-        if result > 0 {
+        if result >= 0 {
+            coop.made_progress();
             return Poll::Ready(Ok(result as usize));
         }
 
-        if result == 0 {
-            return Poll::Ready(Ok(0));
-        }
-
         let error = unsafe { *libc::__error() };
 
         if error == EAGAIN || error == EWOULDBLOCK {
-            if !this.registered {
-                this.reactor
-                    .lock()
-                    .unwrap()
-                    .register_read(this.file_descriptor, cx.waker().clone());
-                this.registered = true;
-            }
+            this.reactor
+                .lock()
+                .unwrap()
+                .register_read(this.file_descriptor, cx.waker().clone());
 
             return Poll::Pending;
         }
 
+        coop.made_progress();
         Poll::Ready(Err(io::Error::last_os_error()))
     }
 }
@@ -69,7 +65,6 @@ pub struct WriteFuture<'a> {
     file_descriptor: i32,
     buffer: &'a [u8],
     reactor: ReactorHandle,
-    registered: bool,
 }
 
 impl<'a> WriteFuture<'a> {
@@ -78,7 +73,6 @@ impl<'a> WriteFuture<'a> {
             file_descriptor,
             buffer,
             reactor,
-            registered: false,
         }
     }
 }
@@ -87,6 +81,8 @@ impl<'a> Future for WriteFuture<'a> {
     type Output = io::Result<usize>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let coop = ready!(poll_proceed(cx));
+
         let this = self.as_mut().get_mut();
 
         let result = unsafe {
@@ -98,23 +94,22 @@ impl<'a> Future for WriteFuture<'a> {
         };
 
         if result >= 0 {
+            coop.made_progress();
             return Poll::Ready(Ok(result as usize));
         }
 
         let error = unsafe { *libc::__error() };
 
         if error == EAGAIN || error == EWOULDBLOCK {
-            if !this.registered {
-                this.reactor
-                    .lock()
-                    .unwrap()
-                    .register_write(this.file_descriptor, cx.waker().clone());
-                this.registered = true;
-            }
+            this.reactor
+                .lock()
+                .unwrap()
+                .register_write(this.file_descriptor, cx.waker().clone());
 
             return Poll::Pending;
         }
 
+        coop.made_progress();
         Poll::Ready(Err(io::Error::last_os_error()))
     }
 }
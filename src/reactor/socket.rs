@@ -30,7 +30,7 @@ pub(crate) fn accept_client(
 
     Event::set_nonblocking(client_file_descriptor);
 
-    let event = Event::new(client_file_descriptor as usize, EVFILT_READ, None);
+    let event = Event::new(client_file_descriptor as usize, EVFILT_READ);
     event.register(queue);
 
     registry.insert(client_file_descriptor, Entry::Client(Connection::new()));
@@ -0,0 +1,183 @@
+use crate::core::task::{JoinHandle, Task, TaskConfig};
+use crate::runtime::workstealing::Injector;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::panic::Location;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// Returned by [`Handle::try_spawn`](crate::Handle::try_spawn) when the runtime
+/// already has as many live tasks as `RuntimeBuilder::max_tasks` allows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpawnError;
+
+impl fmt::Display for SpawnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("task limit reached")
+    }
+}
+
+impl std::error::Error for SpawnError {}
+
+/// Spawners parked in [`SpawnWait`] until a task completes, oldest first.
+#[derive(Default)]
+pub(crate) struct CapacityWaiters {
+    list: Mutex<WaitList>,
+}
+
+#[derive(Default)]
+struct WaitList {
+    next_key: u64,
+    waiters: VecDeque<(u64, Waker)>,
+}
+
+impl CapacityWaiters {
+    /// Queues the waiter `key`, assigning a key on first use, or refreshes its
+    /// waker when it is still queued.
+    fn register(&self, key: &mut Option<u64>, waker: &Waker) {
+        let mut list = self.list.lock().unwrap();
+
+        if let Some(key) = *key
+            && let Some((_, queued)) = list.waiters.iter_mut().find(|(k, _)| *k == key)
+        {
+            queued.clone_from(waker);
+            return;
+        }
+
+        let key = *key.get_or_insert_with(|| {
+            list.next_key += 1;
+            list.next_key
+        });
+
+        list.waiters.push_back((key, waker.clone()));
+    }
+
+    /// Returns `false` when the waiter was already dequeued by [`wake_one`].
+    ///
+    /// [`wake_one`]: CapacityWaiters::wake_one
+    fn remove(&self, key: u64) -> bool {
+        let mut list = self.list.lock().unwrap();
+
+        match list.waiters.iter().position(|(k, _)| *k == key) {
+            Some(index) => {
+                list.waiters.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn wake_one(&self) {
+        let waiter = self.list.lock().unwrap().waiters.pop_front();
+
+        if let Some((_, waker)) = waiter {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn wake_all(&self) {
+        let waiters = std::mem::take(&mut self.list.lock().unwrap().waiters);
+
+        for (_, waker) in waiters {
+            waker.wake();
+        }
+    }
+}
+
+/// Spawns a task once the runtime is below its task limit. Returned by
+/// [`Handle::spawn_wait`](crate::Handle::spawn_wait).
+#[must_use = "futures do nothing unless polled"]
+pub struct SpawnWait<F, T> {
+    injector: Arc<Injector>,
+    future: Option<F>,
+    location: &'static Location<'static>,
+    key: Option<u64>,
+    _output: PhantomData<fn() -> T>,
+}
+
+// `future` is moved into a task, never polled in place.
+impl<F, T> Unpin for SpawnWait<F, T> {}
+
+impl<F, T> SpawnWait<F, T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + Sync + 'static,
+{
+    pub(crate) fn new(
+        injector: Arc<Injector>,
+        future: F,
+        location: &'static Location<'static>,
+    ) -> Self {
+        Self {
+            injector,
+            future: Some(future),
+            location,
+            key: None,
+            _output: PhantomData,
+        }
+    }
+
+    fn try_spawn(&mut self) -> Option<JoinHandle<T>> {
+        // A closed runtime cancels the task as soon as it is pushed, so waiting
+        // for a slot would never end.
+        if self.injector.is_closed() {
+            self.injector.admit();
+        } else if !self.injector.try_admit() {
+            return None;
+        }
+
+        if let Some(key) = self.key.take() {
+            self.injector.capacity.remove(key);
+        }
+
+        let future = self
+            .future
+            .take()
+            .expect("SpawnWait polled after completion");
+        let config = TaskConfig {
+            location: Some(self.location),
+            ..TaskConfig::default()
+        };
+
+        Some(Task::spawn_admitted(future, self.injector.clone(), config))
+    }
+}
+
+impl<F, T> Future for SpawnWait<F, T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + Sync + 'static,
+{
+    type Output = JoinHandle<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<JoinHandle<T>> {
+        let this = self.get_mut();
+
+        if let Some(handle) = this.try_spawn() {
+            return Poll::Ready(handle);
+        }
+
+        this.injector.capacity.register(&mut this.key, cx.waker());
+
+        // A task may have completed before the waker was queued.
+        match this.try_spawn() {
+            Some(handle) => Poll::Ready(handle),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<F, T> Drop for SpawnWait<F, T> {
+    fn drop(&mut self) {
+        // Woken for a free slot but never took it: pass the wakeup on.
+        if let Some(key) = self.key
+            && !self.injector.capacity.remove(key)
+        {
+            self.injector.capacity.wake_one();
+        }
+    }
+}
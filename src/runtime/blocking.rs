@@ -0,0 +1,202 @@
+use crate::core::task::JoinHandle;
+use crate::runtime::workstealing::CURRENT_WORKER;
+use crate::runtime::{Handle, exit_runtime};
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+pub(crate) const DEFAULT_MAX_BLOCKING_THREADS: usize = 512;
+pub(crate) const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(10);
+
+type Job = Box<dyn FnOnce() + Send>;
+
+struct State {
+    queue: VecDeque<Job>,
+    threads: usize,
+    idle: usize,
+    notified: usize,
+    shutdown: bool,
+}
+
+pub(crate) struct BlockingPool {
+    state: Mutex<State>,
+    condvar: Condvar,
+    max_threads: usize,
+    keep_alive: Duration,
+}
+
+impl BlockingPool {
+    pub(crate) fn new(max_threads: usize, keep_alive: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(State {
+                queue: VecDeque::new(),
+                threads: 0,
+                idle: 0,
+                notified: 0,
+                shutdown: false,
+            }),
+            condvar: Condvar::new(),
+            max_threads: max_threads.max(1),
+            keep_alive,
+        })
+    }
+
+    pub(crate) fn spawn(self: &Arc<Self>, job: Job) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.shutdown {
+            return;
+        }
+
+        state.queue.push_back(job);
+
+        if state.idle > 0 {
+            state.idle -= 1;
+            state.notified += 1;
+            self.condvar.notify_one();
+        } else if state.threads < self.max_threads {
+            state.threads += 1;
+
+            let pool = self.clone();
+            std::thread::spawn(move || pool.run());
+        }
+    }
+
+    /// Whether no job is queued or running.
+    pub(crate) fn is_idle(&self) -> bool {
+        let state = self.state.lock().unwrap();
+
+        state.queue.is_empty() && state.threads == state.idle
+    }
+
+    pub(crate) fn shutdown(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        state.shutdown = true;
+        state.queue.clear();
+
+        self.condvar.notify_all();
+    }
+
+    fn run(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        'outer: loop {
+            while let Some(job) = state.queue.pop_front() {
+                drop(state);
+
+                let _ = catch_unwind(AssertUnwindSafe(job));
+
+                state = self.state.lock().unwrap();
+            }
+
+            if state.shutdown {
+                break;
+            }
+
+            state.idle += 1;
+
+            loop {
+                let (guard, result) = self.condvar.wait_timeout(state, self.keep_alive).unwrap();
+                state = guard;
+
+                if state.notified > 0 {
+                    state.notified -= 1;
+                    break;
+                }
+
+                if state.shutdown || result.timed_out() {
+                    state.idle -= 1;
+                    break 'outer;
+                }
+            }
+        }
+
+        state.threads -= 1;
+    }
+}
+
+struct Slot<R> {
+    result: Option<Result<R, Box<dyn Any + Send>>>,
+    waker: Option<Waker>,
+}
+
+struct BlockingFuture<R> {
+    slot: Arc<Mutex<Slot<R>>>,
+}
+
+impl<R> Future for BlockingFuture<R> {
+    type Output = R;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.lock().unwrap();
+
+        match slot.result.take() {
+            Some(Ok(value)) => Poll::Ready(value),
+            Some(Err(payload)) => resume_unwind(payload),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Runs `function` on the runtime's blocking thread pool and returns a handle to its result.
+///
+/// The pool grows on demand up to `RuntimeBuilder::max_blocking_threads` and idle
+/// threads exit after `RuntimeBuilder::thread_keep_alive`.
+#[track_caller]
+pub fn spawn_blocking<F, R>(function: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + Sync + 'static,
+{
+    let handle =
+        Handle::try_current().expect("spawn_blocking() called outside of a runtime context");
+    let context = handle.clone();
+    let slot = Arc::new(Mutex::new(Slot {
+        result: None,
+        waker: None,
+    }));
+    let job_slot = slot.clone();
+
+    handle.blocking.spawn(Box::new(move || {
+        let _guard = context.enter();
+        let result = catch_unwind(AssertUnwindSafe(function));
+
+        let mut slot = job_slot.lock().unwrap();
+        slot.result = Some(result);
+
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }));
+
+    handle.spawn(BlockingFuture { slot })
+}
+
+/// Runs `function` in place on the current worker, handing its local queue back to the
+/// runtime first so queued tasks can be picked up by other workers meanwhile. Tasks
+/// pinned to the worker stay queued until it returns.
+///
+/// Outside of a worker thread the function is simply called. Either way it may call
+/// `block_on`, for instance to drive another runtime.
+pub fn block_in_place<F, R>(function: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    CURRENT_WORKER.with(|cell| {
+        if let Some(worker) = cell.borrow().as_ref() {
+            worker.hand_off_local_queue();
+        }
+    });
+
+    exit_runtime(function)
+}
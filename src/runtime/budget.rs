@@ -0,0 +1,103 @@
+use std::cell::Cell;
+use std::future::{Future, poll_fn};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+/// Number of resource operations a task may complete in one poll before it is
+/// forced to yield back to the scheduler.
+const INITIAL_BUDGET: u8 = 128;
+
+thread_local! {
+    // `None` means unconstrained: either outside of a task or inside `unconstrained`.
+    static BUDGET: Cell<Option<u8>> = const { Cell::new(None) };
+}
+
+/// Runs `f` with a fresh budget, restoring the previous one afterwards.
+pub(crate) fn with_budget<R>(f: impl FnOnce() -> R) -> R {
+    with(Some(INITIAL_BUDGET), f)
+}
+
+fn with<R>(budget: Option<u8>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<u8>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            BUDGET.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(BUDGET.with(|cell| cell.replace(budget)));
+
+    f()
+}
+
+/// Consumes one unit of budget, or schedules a wake-up and returns `Pending`
+/// when the budget is exhausted.
+///
+/// The unit is given back when the returned guard is dropped, unless the
+/// operation calls [`RestoreOnPending::made_progress`]: a resource that is not
+/// ready yet does not count against the task.
+pub(crate) fn poll_proceed(cx: &mut Context<'_>) -> Poll<RestoreOnPending> {
+    BUDGET.with(|cell| match cell.get() {
+        Some(0) => {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+        Some(n) => {
+            cell.set(Some(n - 1));
+            Poll::Ready(RestoreOnPending(Cell::new(true)))
+        }
+        None => Poll::Ready(RestoreOnPending(Cell::new(false))),
+    })
+}
+
+/// Returned by [`poll_proceed`]. Refunds the consumed unit on drop unless the
+/// operation made progress.
+pub(crate) struct RestoreOnPending(Cell<bool>);
+
+impl RestoreOnPending {
+    pub(crate) fn made_progress(&self) {
+        self.0.set(false);
+    }
+}
+
+impl Drop for RestoreOnPending {
+    fn drop(&mut self) {
+        if self.0.get() {
+            BUDGET.with(|cell| cell.set(cell.get().map(|n| n.saturating_add(1))));
+        }
+    }
+}
+
+/// Consumes one unit of the task's budget, yielding if it is exhausted.
+///
+/// Useful in loops that do not otherwise touch runtime resources.
+pub async fn consume_budget() {
+    poll_fn(|cx| {
+        let coop = ready!(poll_proceed(cx));
+        coop.made_progress();
+
+        Poll::Ready(())
+    })
+    .await
+}
+
+/// Future returned by [`unconstrained`].
+pub struct Unconstrained<F> {
+    future: F,
+}
+
+/// Runs `future` without a budget, so it is never forced to yield.
+pub fn unconstrained<F: Future>(future: F) -> Unconstrained<F> {
+    Unconstrained { future }
+}
+
+impl<F: Future> Future for Unconstrained<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let future = unsafe { self.map_unchecked_mut(|this| &mut this.future) };
+
+        with(None, || future.poll(cx))
+    }
+}
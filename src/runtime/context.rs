@@ -1,78 +1,134 @@
+use crate::error::ContextError;
 use crate::reactor::core::ReactorHandle;
-use crate::runtime::workstealing::{CURRENT_INJECTOR, Injector};
+use crate::runtime::Handle;
+use crate::runtime::blocking::BlockingPool;
+use crate::time::clock::Clock;
+use crate::time::driver::TimeDriver;
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
 use std::sync::Arc;
+use std::thread::LocalKey;
 
 #[derive(Clone, Copy, Debug)]
 pub(crate) struct Features {
     pub(crate) io_enabled: bool,
     pub(crate) fs_enabled: bool,
+    pub(crate) time_enabled: bool,
 }
 
 thread_local! {
     pub(crate) static CURRENT_REACTOR: RefCell<Option<ReactorHandle>> = const { RefCell::new(None) };
     pub(crate) static CURRENT_FEATURES: RefCell<Option<Features>> = const { RefCell::new(None) };
+    pub(crate) static CURRENT_BLOCKING: RefCell<Option<Arc<BlockingPool>>> = const { RefCell::new(None) };
+    pub(crate) static CURRENT_CLOCK: RefCell<Option<Arc<Clock>>> = const { RefCell::new(None) };
+    pub(crate) static CURRENT_TIME: RefCell<Option<Arc<TimeDriver>>> = const { RefCell::new(None) };
+
+    /// Whether this thread runs tasks: a worker, or a thread inside `block_on`.
+    static IN_RUNTIME: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Marks the current thread as running tasks until dropped. Returned by [`enter_runtime`].
+pub(crate) struct RuntimeGuard {
+    _not_send: PhantomData<*const ()>,
 }
 
-pub(crate) fn enter_context<F, R>(
-    injector: Arc<Injector>,
-    reactor: ReactorHandle,
-    features: Features,
-    function: F,
-) -> R
+impl Drop for RuntimeGuard {
+    fn drop(&mut self) {
+        IN_RUNTIME.with(|cell| cell.set(false));
+    }
+}
+
+/// Marks the current thread as running tasks.
+///
+/// # Panics
+///
+/// Panics when the thread already runs tasks: blocking it on another future
+/// would stall every task that needs it, up to waiting on itself.
+#[track_caller]
+pub(crate) fn enter_runtime() -> RuntimeGuard {
+    if IN_RUNTIME.with(|cell| cell.replace(true)) {
+        panic!(
+            "cannot block_on from within a runtime: the thread is already running tasks. \
+             Use task::spawn_blocking to drive another runtime from a task."
+        );
+    }
+
+    RuntimeGuard {
+        _not_send: PhantomData,
+    }
+}
+
+/// Runs `function` as if the current thread did not run tasks, so that it may
+/// call `block_on`.
+pub(crate) fn exit_runtime<F, R>(function: F) -> R
 where
     F: FnOnce() -> R,
 {
-    CURRENT_INJECTOR.with(|current_injector| {
-        CURRENT_REACTOR.with(|current_reactor| {
-            CURRENT_FEATURES.with(|current_features| {
-                let prev_injector = current_injector.borrow_mut().replace(injector);
-                let prev_reactor = current_reactor.borrow_mut().replace(reactor);
-                let prev_features = current_features.borrow_mut().replace(features);
-
-                let result = function();
-
-                *current_injector.borrow_mut() = prev_injector;
-                *current_reactor.borrow_mut() = prev_reactor;
-                *current_features.borrow_mut() = prev_features;
-
-                result
-            })
-        })
-    })
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            IN_RUNTIME.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(IN_RUNTIME.with(|cell| cell.replace(false)));
+
+    function()
 }
 
-pub(crate) fn current_reactor_io() -> ReactorHandle {
-    ensure_feature(|f| f.io_enabled, "I/O", "RuntimeBuilder::enable_io()");
+pub(crate) fn enter_context<F, R>(handle: &Handle, function: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let _guard = handle.enter();
 
-    current_reactor_inner()
+    function()
+}
+
+pub(crate) fn current_reactor_io() -> ReactorHandle {
+    try_current_reactor_io().unwrap_or_else(|error| panic!("{}", error))
 }
 
 pub(crate) fn current_reactor_fs() -> ReactorHandle {
-    ensure_feature(
-        |f| f.fs_enabled,
-        "filesystem",
-        "RuntimeBuilder::enable_fs()",
-    );
+    try_current_reactor_fs().unwrap_or_else(|error| panic!("{}", error))
+}
 
-    current_reactor_inner()
+pub(crate) fn current_time_driver() -> Arc<TimeDriver> {
+    try_current_time_driver().unwrap_or_else(|error| panic!("{}", error))
 }
 
-fn ensure_feature(check: impl Fn(&Features) -> bool, name: &str, hint: &str) {
-    CURRENT_FEATURES.with(|features| {
-        let enabled = features.borrow().as_ref().map(check).unwrap_or(false);
+pub(crate) fn try_current_reactor_io() -> Result<ReactorHandle, ContextError> {
+    try_current(&CURRENT_REACTOR, |f| f.io_enabled, ContextError::IoDisabled)
+}
 
-        if !enabled {
-            panic!("{} support not enabled. Use {}.", name, hint);
-        }
-    })
+pub(crate) fn try_current_reactor_fs() -> Result<ReactorHandle, ContextError> {
+    try_current(&CURRENT_REACTOR, |f| f.fs_enabled, ContextError::FsDisabled)
+}
+
+pub(crate) fn try_current_time_driver() -> Result<Arc<TimeDriver>, ContextError> {
+    try_current(
+        &CURRENT_TIME,
+        |f| f.time_enabled,
+        ContextError::TimeDisabled,
+    )
 }
 
-fn current_reactor_inner() -> ReactorHandle {
-    CURRENT_REACTOR.with(|current| {
-        current.borrow().clone().expect(
-            "No reactor in current context. I/O operations must be called within Runtime::block_on",
-        )
-    })
+fn try_current<T: Clone>(
+    current: &'static LocalKey<RefCell<Option<T>>>,
+    enabled: impl FnOnce(&Features) -> bool,
+    disabled: ContextError,
+) -> Result<T, ContextError> {
+    let features = CURRENT_FEATURES
+        .with(|cell| *cell.borrow())
+        .ok_or(ContextError::NoRuntime)?;
+
+    if !enabled(&features) {
+        return Err(disabled);
+    }
+
+    current
+        .with(|cell| cell.borrow().clone())
+        .ok_or(ContextError::NoRuntime)
 }
@@ -1,151 +1,183 @@
-use crate::core::task::Runnable;
+use crate::RuntimeBuilder;
 use crate::reactor::core::{Reactor, ReactorHandle};
+use crate::runtime::blocking::BlockingPool;
+use crate::runtime::executor::{Pool, WorkerConfig};
+use crate::runtime::hooks::Hooks;
+use crate::runtime::watchdog;
 use crate::runtime::workstealing::Injector;
-use crate::runtime::{Executor, Features, enter_context};
-use crate::{RuntimeBuilder, Task};
+use crate::runtime::{Executor, Features, Handle, ShutdownReport, enter_context};
+use crate::time::clock::Clock;
+use crate::time::driver::TimeDriver;
 
 use std::future::Future;
-use std::sync::atomic::Ordering;
 use std::sync::{Arc, Mutex};
-use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
+/// Dropping a runtime shuts it down like [`Runtime::shutdown_timeout`], with
+/// the timeout set by `RuntimeBuilder::drop_timeout`, so a task stuck in a poll
+/// cannot hang the dropping thread. Blocking jobs are never waited for.
 pub struct Runtime {
-    injector: Arc<Injector>,
-    reactor: ReactorHandle,
-    io_enabled: bool,
-    fs_enabled: bool,
+    handle: Handle,
+    executor: Executor,
+    seed: Option<u64>,
+    drop_timeout: Duration,
 }
 
 impl Runtime {
-    pub(crate) fn with_features(io_enabled: bool, fs_enabled: bool) -> Self {
-        let injector = Arc::new(Injector::new());
-        let reactor = Arc::new(Mutex::new(Reactor::new()));
-
-        let features = Features {
-            io_enabled,
-            fs_enabled,
+    pub(crate) fn with_features(
+        features: Features,
+        blocking: Arc<BlockingPool>,
+        workers: WorkerConfig,
+        max_tasks: Option<usize>,
+        seed: Option<u64>,
+        start_paused: bool,
+        hooks: Hooks,
+    ) -> Self {
+        // A simulated runtime has no workers: every task runs on the thread calling
+        // `block_on`, in an order picked by the seed, against a paused clock.
+        let pool = match seed {
+            Some(_) => Pool::new(0, 0, None),
+            None => workers.pool(),
+        };
+        let num_workers = pool.slots();
+        let drop_timeout = workers.drop_timeout;
+        let thread_per_core = workers.cores.is_some() && seed.is_none();
+        let clock = Arc::new(Clock::new(seed.is_some() || start_paused));
+        let reactor = Reactor::new();
+        let unpark = reactor.unparker();
+        let time = Arc::new(TimeDriver::new(clock.clone(), unpark.clone()));
+
+        let handle = Handle {
+            injector: Arc::new(Injector::new(
+                seed,
+                pool,
+                thread_per_core,
+                max_tasks,
+                hooks,
+                unpark,
+            )),
+            reactor: Arc::new(Mutex::new(reactor)),
+            features,
+            blocking,
+            clock,
+            time,
         };
 
-        let mut executor = Executor::new(injector.clone(), reactor.clone(), features);
+        let cores = workers.cores.as_deref().filter(|_| thread_per_core);
+        let mut executor = Executor::new(handle.clone(), cores);
 
         executor.start();
 
+        // Only workers can get stuck; a simulated runtime still reports slow polls.
+        if let Some(watchdog) = handle.injector.hooks.watchdog.clone()
+            && num_workers > 0
+        {
+            watchdog::spawn(&handle.injector, watchdog);
+        }
+
         Self {
-            injector,
-            reactor,
-            io_enabled,
-            fs_enabled,
+            handle,
+            executor,
+            seed,
+            drop_timeout,
         }
     }
 
+    /// Returns a cloneable handle that can spawn onto this runtime from any thread.
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    #[track_caller]
     pub fn spawn<F: Future<Output = ()> + Send + 'static>(&self, future: F) {
-        let injector = self.injector.clone();
-        let task = Task::new(future, injector.clone());
-        let r: Arc<dyn Runnable> = task.clone();
-        task.inqueue.store(true, Ordering::Release);
-        injector.push(r);
+        self.handle.spawn(future);
     }
 
+    /// Runs `future` to completion on the current thread. See [`Handle::block_on`].
+    #[track_caller]
     pub fn block_on<F: Future>(&self, future: F) -> F::Output {
-        let features = Features {
-            io_enabled: self.io_enabled,
-            fs_enabled: self.fs_enabled,
+        self.handle.block_on(future)
+    }
+
+    /// Runs the runtime on the current thread until every spawned task has completed.
+    #[track_caller]
+    pub fn run_until_idle(&self) {
+        self.handle.run_until_idle();
+    }
+
+    /// Shuts the runtime down, waiting at most `timeout` for the workers to stop.
+    ///
+    /// New spawns are rejected, every pending task has its future dropped inside the
+    /// runtime context, and the reactor is closed. Tasks still being polled when the
+    /// timeout elapses are reported in [`ShutdownReport::still_running`].
+    pub fn shutdown_timeout(mut self, timeout: Duration) -> ShutdownReport {
+        self.shutdown_inner(Instant::now().checked_add(timeout))
+    }
+
+    /// Shuts the runtime down without waiting for the workers to stop.
+    pub fn shutdown_background(mut self) {
+        self.shutdown_inner(Some(Instant::now()));
+    }
+
+    fn shutdown_inner(&mut self, deadline: Option<Instant>) -> ShutdownReport {
+        let handle = &self.handle;
+
+        if handle.injector.is_closed() {
+            return ShutdownReport::default();
+        }
+
+        handle.injector.close();
+        handle.blocking.shutdown();
+
+        let workers_joined = self.executor.join(deadline);
+
+        let mut report = ShutdownReport {
+            workers_joined,
+            ..ShutdownReport::default()
         };
 
-        enter_context(
-            self.injector.clone(),
-            self.reactor.clone(),
-            features,
-            || {
-                let mut future = Box::pin(future);
-                let mut is_notified = false;
-                let mut root_done = false;
-                let mut root_value = None;
-
-                fn clone_waker(data: *const ()) -> std::task::RawWaker {
-                    std::task::RawWaker::new(data, &VTABLE)
-                }
-                fn wake(data: *const ()) {
-                    unsafe {
-                        *(data as *mut bool) = true;
-                    }
-                }
-                fn wake_by_ref(data: *const ()) {
-                    unsafe {
-                        *(data as *mut bool) = true;
-                    }
+        enter_context(handle, || {
+            for task in handle.injector.drain() {
+                if task.cancel() {
+                    handle.injector.task_completed(task.id());
+                    report.cancelled.push(task.id());
+                } else if task.is_running() {
+                    report.running.push(task.id());
                 }
-                fn drop_waker(_: *const ()) {}
-
-                static VTABLE: std::task::RawWakerVTable =
-                    std::task::RawWakerVTable::new(clone_waker, wake, wake_by_ref, drop_waker);
-
-                let raw =
-                    std::task::RawWaker::new(&mut is_notified as *mut bool as *const (), &VTABLE);
-                let waker = unsafe { std::task::Waker::from_raw(raw) };
-                let mut cx = Context::from_waker(&waker);
-
-                loop {
-                    if !root_done && let Poll::Ready(v) = future.as_mut().poll(&mut cx) {
-                        root_done = true;
-                        root_value = Some(v);
-                    }
-
-                    {
-                        let mut r = self.reactor.lock().unwrap();
-                        r.poll_events();
-                        r.wake_ready();
-                    }
-
-                    if root_done && self.injector.is_idle() {
-                        return root_value.unwrap();
-                    }
-
-                    while let Some(task) = self.injector.pop() {
-                        task.poll();
-
-                        {
-                            let mut r = self.reactor.lock().unwrap();
-                            r.poll_events();
-                            r.wake_ready();
-                        }
-
-                        if root_done && self.injector.is_idle() {
-                            return root_value.unwrap();
-                        }
-                    }
-
-                    if root_done && self.injector.is_idle() {
-                        return root_value.unwrap();
-                    }
-
-                    if is_notified {
-                        is_notified = false;
-                        continue;
-                    }
-
-                    std::thread::yield_now();
-                }
-            },
-        )
+            }
+        });
+
+        handle.reactor.lock().unwrap().shutdown();
+        handle.time.shutdown();
+
+        report
+    }
+
+    /// Seed of a simulated runtime, to be logged so a failing run can be replayed.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
     }
 
     pub fn reactor_handle(&self) -> ReactorHandle {
-        self.reactor.clone()
+        self.handle.reactor.clone()
     }
 
     pub fn io_enabled(&self) -> bool {
-        self.io_enabled
+        self.handle.features.io_enabled
     }
 
     pub fn fs_enabled(&self) -> bool {
-        self.fs_enabled
+        self.handle.features.fs_enabled
+    }
+
+    pub fn time_enabled(&self) -> bool {
+        self.handle.features.time_enabled
     }
 }
 
 impl Drop for Runtime {
     fn drop(&mut self) {
-        self.injector.shutdown();
+        self.shutdown_inner(Instant::now().checked_add(self.drop_timeout));
     }
 }
 
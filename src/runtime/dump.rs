@@ -0,0 +1,134 @@
+use crate::core::task::Id;
+
+use std::fmt;
+use std::panic::Location;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Per-task bookkeeping kept only when the `taskdump` feature is enabled.
+pub(crate) struct Trace {
+    polls: AtomicU64,
+    last_poll: Mutex<Option<Instant>>,
+}
+
+impl Trace {
+    pub(crate) fn new() -> Self {
+        Self {
+            polls: AtomicU64::new(0),
+            last_poll: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn record_poll(&self) {
+        self.polls.fetch_add(1, Ordering::Relaxed);
+        *self.last_poll.lock().unwrap() = Some(Instant::now());
+    }
+
+    pub(crate) fn info(
+        &self,
+        id: Id,
+        name: Option<&str>,
+        state: TaskState,
+        location: &'static Location<'static>,
+    ) -> TaskInfo {
+        TaskInfo {
+            id,
+            name: name.map(str::to_owned),
+            state,
+            polls: self.polls.load(Ordering::Relaxed),
+            since_last_poll: self.last_poll.lock().unwrap().map(|at| at.elapsed()),
+            location,
+        }
+    }
+}
+
+/// Scheduling state of a live task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskState {
+    /// Waiting for a wake-up.
+    Idle,
+    /// Queued, waiting for a worker.
+    Scheduled,
+    /// Being polled right now.
+    Running,
+}
+
+/// One live task in a [`TaskDump`].
+#[derive(Clone, Debug)]
+pub struct TaskInfo {
+    id: Id,
+    name: Option<String>,
+    state: TaskState,
+    polls: u64,
+    since_last_poll: Option<Duration>,
+    location: &'static Location<'static>,
+}
+
+impl TaskInfo {
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn state(&self) -> TaskState {
+        self.state
+    }
+
+    pub fn poll_count(&self) -> u64 {
+        self.polls
+    }
+
+    /// `None` when the task has never been polled.
+    pub fn since_last_poll(&self) -> Option<Duration> {
+        self.since_last_poll
+    }
+
+    /// Where the task was spawned.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+}
+
+/// Snapshot of every live task, returned by `Handle::dump`.
+#[derive(Clone, Debug, Default)]
+pub struct TaskDump {
+    tasks: Vec<TaskInfo>,
+}
+
+impl TaskDump {
+    pub(crate) fn new(tasks: Vec<TaskInfo>) -> Self {
+        Self { tasks }
+    }
+
+    /// Live tasks, ordered by ID.
+    pub fn tasks(&self) -> &[TaskInfo] {
+        &self.tasks
+    }
+}
+
+impl fmt::Display for TaskDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for task in &self.tasks {
+            write!(f, "task {}", task.id)?;
+
+            if let Some(name) = &task.name {
+                write!(f, " ({})", name)?;
+            }
+
+            write!(f, " {:?} polls={}", task.state, task.polls)?;
+
+            match task.since_last_poll {
+                Some(elapsed) => write!(f, " last_poll={:?} ago", elapsed)?,
+                None => write!(f, " never polled")?,
+            }
+
+            writeln!(f, " spawned at {}", task.location)?;
+        }
+
+        Ok(())
+    }
+}
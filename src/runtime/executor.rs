@@ -1,53 +1,266 @@
-use crate::reactor::core::ReactorHandle;
-use crate::runtime::context::Features;
-use crate::runtime::workstealing::{Injector, LocalQueue, Worker};
+use crate::runtime::Handle;
+use crate::runtime::workstealing::{Injector, Worker};
 
-use std::sync::Arc;
-use std::thread::JoinHandle;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+pub(crate) const DEFAULT_WORKER_KEEP_ALIVE: Duration = Duration::from_secs(10);
+pub(crate) const DEFAULT_DROP_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub(crate) struct Executor {
-    workers: Vec<Arc<Worker>>,
-    handles: Vec<JoinHandle<()>>,
+    handle: Handle,
+    cores: Option<Vec<usize>>,
 }
 
-impl Executor {
-    pub(crate) fn new(queue: Arc<Injector>, reactor: ReactorHandle, features: Features) -> Self {
-        let num_workers = num_cpus().max(1);
-        let locals = Arc::new(
-            (0..num_workers)
-                .map(|_| LocalQueue::new())
-                .collect::<Vec<_>>(),
-        );
+/// Worker settings collected by `RuntimeBuilder`.
+#[derive(Clone, Debug)]
+pub(crate) struct WorkerConfig {
+    pub(crate) threads: Option<usize>,
+    /// Thread-per-core mode: one worker pinned to each listed core.
+    pub(crate) cores: Option<Vec<usize>>,
+    pub(crate) min: Option<usize>,
+    pub(crate) max: Option<usize>,
+    pub(crate) keep_alive: Duration,
+    /// How long dropping the runtime waits for the workers to stop.
+    pub(crate) drop_timeout: Duration,
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        Self {
+            threads: None,
+            cores: None,
+            min: None,
+            max: None,
+            keep_alive: DEFAULT_WORKER_KEEP_ALIVE,
+            drop_timeout: DEFAULT_DROP_TIMEOUT,
+        }
+    }
+}
+
+impl WorkerConfig {
+    /// Setting either bound makes the pool elastic, except in thread-per-core mode.
+    fn is_elastic(&self) -> bool {
+        self.cores.is_none() && (self.min.is_some() || self.max.is_some())
+    }
+
+    /// Most workers that can run at once.
+    fn slots(&self) -> usize {
+        let min = self.min.unwrap_or(0);
+
+        match (&self.cores, self.max, self.threads) {
+            (Some(cores), _, _) => cores.len(),
+            (None, Some(max), _) => max.max(min),
+            (None, None, Some(threads)) => threads.max(min),
+            (None, None, None) => num_cpus().max(min),
+        }
+    }
+
+    pub(crate) fn pool(&self) -> Pool {
+        let slots = self.slots();
+
+        if self.is_elastic() {
+            Pool::new(slots, self.min.unwrap_or(1), Some(self.keep_alive))
+        } else {
+            Pool::new(slots, slots, None)
+        }
+    }
+}
+
+/// Worker slots and the threads running them.
+///
+/// A fixed pool fills every slot at startup. An elastic pool starts `min`
+/// workers, adds one whenever the shared queue holds more tasks than running
+/// workers and none of them is parked, and retires workers that stayed parked
+/// for `keep_alive`.
+pub(crate) struct Pool {
+    running: Vec<AtomicBool>,
+    active: AtomicUsize,
+    min: usize,
+    keep_alive: Option<Duration>,
+    live: Mutex<usize>,
+    exited: Condvar,
+}
+
+impl Pool {
+    pub(crate) fn new(slots: usize, min: usize, keep_alive: Option<Duration>) -> Self {
+        Self {
+            running: (0..slots).map(|_| AtomicBool::new(false)).collect(),
+            active: AtomicUsize::new(0),
+            min: min.min(slots),
+            keep_alive,
+            live: Mutex::new(0),
+            exited: Condvar::new(),
+        }
+    }
+
+    pub(crate) fn slots(&self) -> usize {
+        self.running.len()
+    }
+
+    /// Workers currently running.
+    pub(crate) fn active(&self) -> usize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn is_running(&self, slot: usize) -> bool {
+        self.running[slot].load(Ordering::Acquire)
+    }
+
+    /// How long a parked worker waits before retiring, for an elastic pool.
+    pub(crate) fn keep_alive(&self) -> Option<Duration> {
+        self.keep_alive
+    }
+
+    fn start(&self, slot: usize) {
+        self.running[slot].store(true, Ordering::Release);
+        self.active.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Reserves a free slot for one more worker, if the pool is elastic and not full.
+    pub(crate) fn claim(&self) -> Option<usize> {
+        self.keep_alive?;
+
+        self.active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                (active < self.slots()).then_some(active + 1)
+            })
+            .ok()?;
+
+        // A slot is free for every worker not counted in `active`.
+        loop {
+            for (slot, running) in self.running.iter().enumerate() {
+                if running
+                    .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+                    .is_ok()
+                {
+                    return Some(slot);
+                }
+            }
+        }
+    }
+
+    /// Releases `slot` unless that would leave fewer than `min` workers.
+    pub(crate) fn retire(&self, slot: usize) -> bool {
+        if self.keep_alive.is_none() {
+            return false;
+        }
 
-        let workers = (0..num_workers)
-            .map(|id| {
-                Arc::new(Worker {
-                    id,
-                    locals: locals.clone(),
-                    injector: queue.clone(),
-                    reactor: reactor.clone(),
-                    features,
-                })
+        let retired = self
+            .active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                (active > self.min).then(|| active - 1)
             })
-            .collect();
+            .is_ok();
+
+        if retired {
+            self.running[slot].store(false, Ordering::Release);
+        }
 
+        retired
+    }
+}
+
+impl Executor {
+    pub(crate) fn new(handle: Handle, cores: Option<&[usize]>) -> Self {
         Self {
-            workers,
-            handles: Vec::new(),
+            handle,
+            cores: cores.map(<[usize]>::to_vec),
         }
     }
 
     pub fn start(&mut self) {
-        for worker in &self.workers {
-            let worker = worker.clone();
-            let handle = std::thread::spawn(move || worker.run());
-            self.handles.push(handle);
+        let pool = &self.handle.injector.pool;
+
+        for slot in 0..pool.min {
+            pool.start(slot);
+
+            let core = self.cores.as_ref().map(|cores| cores[slot]);
+            spawn_worker(&self.handle, slot, core);
+        }
+    }
+
+    /// Waits for every worker thread to leave its loop, giving up at `deadline`.
+    /// Returns `true` when all workers were joined.
+    pub(crate) fn join(&mut self, deadline: Option<Instant>) -> bool {
+        let pool = &self.handle.injector.pool;
+        let mut live = pool.live.lock().unwrap();
+
+        while *live > 0 {
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+
+                    if now >= deadline {
+                        break;
+                    }
+
+                    live = pool.exited.wait_timeout(live, deadline - now).unwrap().0;
+                }
+                None => live = pool.exited.wait(live).unwrap(),
+            }
         }
+
+        *live == 0
     }
 }
 
-fn num_cpus() -> usize {
+/// Starts a worker thread for `slot`, which must already be reserved.
+pub(crate) fn spawn_worker(handle: &Handle, slot: usize, core: Option<usize>) {
+    let worker = Arc::new(Worker {
+        id: slot,
+        core,
+        locals: handle.injector.locals.clone(),
+        injector: handle.injector.clone(),
+        handle: handle.clone(),
+    });
+
+    *handle.injector.pool.live.lock().unwrap() += 1;
+    let exit = ExitGuard(handle.injector.clone());
+
+    std::thread::spawn(move || {
+        let _exit = exit;
+
+        worker.run();
+    });
+}
+
+struct ExitGuard(Arc<Injector>);
+
+impl Drop for ExitGuard {
+    fn drop(&mut self) {
+        let pool = &self.0.pool;
+
+        if let Ok(mut live) = pool.live.lock() {
+            *live -= 1;
+        }
+
+        pool.exited.notify_all();
+    }
+}
+
+pub(crate) fn num_cpus() -> usize {
     std::thread::available_parallelism()
         .map(|n| n.get())
         .unwrap_or(1)
 }
+
+/// Pins the calling thread to `core`. macOS has no hard affinity: each core index
+/// becomes a distinct affinity tag, which asks the scheduler to keep the thread
+/// apart from threads with other tags. Apple silicon ignores the hint.
+pub(crate) fn pin_current_thread(core: usize) {
+    let mut policy = libc::thread_affinity_policy {
+        affinity_tag: core as libc::integer_t + 1,
+    };
+
+    // Best effort: an unsupported policy leaves the thread unpinned.
+    unsafe {
+        libc::thread_policy_set(
+            libc::pthread_mach_thread_np(libc::pthread_self()),
+            libc::THREAD_AFFINITY_POLICY as libc::thread_policy_flavor_t,
+            &mut policy as *mut _ as libc::thread_policy_t,
+            libc::THREAD_AFFINITY_POLICY_COUNT,
+        );
+    }
+}
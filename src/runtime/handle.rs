@@ -0,0 +1,392 @@
+use crate::core::task::{JoinHandle, Priority, Task, TaskConfig};
+use crate::reactor::core::{Reactor, ReactorHandle};
+use crate::runtime::admission::{SpawnError, SpawnWait};
+use crate::runtime::blocking::BlockingPool;
+use crate::runtime::budget;
+use crate::runtime::context::{
+    CURRENT_BLOCKING, CURRENT_CLOCK, CURRENT_FEATURES, CURRENT_REACTOR, CURRENT_TIME, Features,
+    enter_runtime,
+};
+use crate::runtime::metrics::RuntimeMetrics;
+use crate::runtime::park::{Parker, Wakeup};
+use crate::runtime::workstealing::{self, BusyGuard, CURRENT_INJECTOR, Injector};
+use crate::task::local::TaskLocals;
+use crate::time::clock::Clock;
+use crate::time::driver::TimeDriver;
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::panic::Location;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+
+/// How long `drive` sleeps before checking for progress nothing notifies it
+/// of: the last task finishing in `run_until_idle`, or another `block_on`
+/// thread of a runtime without workers handing over the reactor.
+const RECHECK_INTERVAL: Duration = Duration::from_millis(1);
+
+/// A cloneable reference to a runtime that can be used from any thread.
+#[derive(Clone)]
+pub struct Handle {
+    pub(crate) injector: Arc<Injector>,
+    pub(crate) reactor: ReactorHandle,
+    pub(crate) features: Features,
+    pub(crate) blocking: Arc<BlockingPool>,
+    pub(crate) clock: Arc<Clock>,
+    pub(crate) time: Arc<TimeDriver>,
+}
+
+impl Handle {
+    /// Returns the handle of the runtime driving the current thread.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called outside of a runtime context.
+    pub fn current() -> Self {
+        Self::try_current().expect("Handle::current() called outside of a runtime context")
+    }
+
+    /// Returns the handle of the runtime driving the current thread, if any.
+    pub fn try_current() -> Option<Self> {
+        let injector = CURRENT_INJECTOR.with(|cell| cell.borrow().clone())?;
+        let reactor = CURRENT_REACTOR.with(|cell| cell.borrow().clone())?;
+        let features = CURRENT_FEATURES.with(|cell| *cell.borrow())?;
+        let blocking = CURRENT_BLOCKING.with(|cell| cell.borrow().clone())?;
+        let clock = CURRENT_CLOCK.with(|cell| cell.borrow().clone())?;
+        let time = CURRENT_TIME.with(|cell| cell.borrow().clone())?;
+
+        Some(Self {
+            injector,
+            reactor,
+            features,
+            blocking,
+            clock,
+            time,
+        })
+    }
+
+    #[track_caller]
+    pub fn spawn<F, T>(&self, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + Sync + 'static,
+    {
+        Task::spawn_with(future, self.injector.clone(), TaskConfig::default())
+    }
+
+    /// Spawns `future` unless the runtime already has `RuntimeBuilder::max_tasks`
+    /// live tasks. Always succeeds on a runtime without a limit.
+    #[track_caller]
+    pub fn try_spawn<F, T>(&self, future: F) -> Result<JoinHandle<T>, SpawnError>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + Sync + 'static,
+    {
+        if !self.injector.try_admit() {
+            return Err(SpawnError);
+        }
+
+        Ok(Task::spawn_admitted(
+            future,
+            self.injector.clone(),
+            TaskConfig::default(),
+        ))
+    }
+
+    /// Spawns `future` once the runtime is below `RuntimeBuilder::max_tasks` live
+    /// tasks. Waiters are woken in the order they started waiting.
+    #[track_caller]
+    pub fn spawn_wait<F, T>(&self, future: F) -> SpawnWait<F, T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + Sync + 'static,
+    {
+        SpawnWait::new(self.injector.clone(), future, Location::caller())
+    }
+
+    /// Lists every live task with its state, poll statistics and spawn location.
+    #[cfg(feature = "taskdump")]
+    pub fn dump(&self) -> crate::runtime::dump::TaskDump {
+        let tasks = self
+            .injector
+            .live_tasks()
+            .iter()
+            .map(|t| t.info())
+            .collect();
+
+        crate::runtime::dump::TaskDump::new(tasks)
+    }
+
+    /// Takes a snapshot of the runtime's scheduler and reactor counters.
+    pub fn metrics(&self) -> RuntimeMetrics {
+        RuntimeMetrics::capture(self)
+    }
+
+    /// Number of tasks waiting in the global run queue of the given class.
+    pub fn queue_depth(&self, priority: Priority) -> usize {
+        self.injector.queue.len(priority)
+    }
+
+    /// Whether every task is waiting on a timer or I/O, including blocking jobs.
+    pub(crate) fn is_quiescent(&self) -> bool {
+        self.injector.is_quiescent() && self.blocking.is_idle()
+    }
+
+    /// Polls the reactor without blocking and fires the timers that are due.
+    pub(crate) fn turn(&self) {
+        let mut reactor = self.reactor.lock().unwrap();
+
+        reactor.poll_events();
+        reactor.wake_ready();
+        self.time.process();
+    }
+
+    /// Installs this runtime as the current context until the guard is dropped,
+    /// so `Task::spawn`, timers and I/O work from a thread the runtime does not own.
+    pub fn enter(&self) -> EnterGuard<'_> {
+        let prev_injector =
+            CURRENT_INJECTOR.with(|cell| cell.borrow_mut().replace(self.injector.clone()));
+        let prev_reactor =
+            CURRENT_REACTOR.with(|cell| cell.borrow_mut().replace(self.reactor.clone()));
+        let prev_features = CURRENT_FEATURES.with(|cell| cell.borrow_mut().replace(self.features));
+        let prev_blocking =
+            CURRENT_BLOCKING.with(|cell| cell.borrow_mut().replace(self.blocking.clone()));
+        let prev_clock = CURRENT_CLOCK.with(|cell| cell.borrow_mut().replace(self.clock.clone()));
+        let prev_time = CURRENT_TIME.with(|cell| cell.borrow_mut().replace(self.time.clone()));
+
+        EnterGuard {
+            prev_injector,
+            prev_reactor,
+            prev_features,
+            prev_blocking,
+            prev_clock,
+            prev_time,
+            _handle: PhantomData,
+        }
+    }
+
+    /// Drives `future` to completion on the current thread, running runtime tasks
+    /// and polling the reactor while it waits.
+    ///
+    /// Returns as soon as `future` completes; detached tasks keep running on the workers.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called from a task or from within another `block_on`, of any
+    /// runtime. Call it from `task::spawn_blocking` or `task::block_in_place`
+    /// instead.
+    #[track_caller]
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.drive(future, false)
+    }
+
+    #[track_caller]
+    pub(crate) fn run_until_idle(&self) {
+        self.drive(async {}, true)
+    }
+
+    #[track_caller]
+    fn drive<F: Future>(&self, future: F, wait_idle: bool) -> F::Output {
+        let _runtime = enter_runtime();
+        let _guard = self.enter();
+
+        let mut future = Box::pin(future);
+        let mut root_value = None;
+        let mut root_locals = TaskLocals::default();
+
+        let root_waker = Arc::new(RootWaker::new(self.injector.clone()));
+        let _finish = FinishRoot(&root_waker);
+        let waker = Waker::from(root_waker.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let is_done = |root_value: &Option<F::Output>| {
+            root_value.is_some() && (!wait_idle || self.injector.is_idle())
+        };
+
+        loop {
+            if root_value.is_none()
+                && let Some(_busy) = root_waker.take()
+                && let Poll::Ready(v) = {
+                    let _locals = TaskLocals::enter(&mut root_locals);
+
+                    budget::with_budget(|| future.as_mut().poll(&mut cx))
+                }
+            {
+                root_value = Some(v);
+            }
+
+            if is_done(&root_value) {
+                return root_value.unwrap();
+            }
+
+            self.turn();
+
+            while self
+                .injector
+                .run_busy(|| self.injector.pop().map(workstealing::run).is_some())
+            {
+                self.turn();
+
+                if root_value.is_none() && root_waker.is_notified() {
+                    break;
+                }
+            }
+
+            if is_done(&root_value) {
+                return root_value.unwrap();
+            }
+
+            if root_value.is_none() && root_waker.is_notified() {
+                continue;
+            }
+
+            // Nothing can run: with a paused clock, jump straight to the next timer.
+            if self.is_quiescent() {
+                // Workers auto-advance under the same lock, so only one thread jumps.
+                let _reactor = self.reactor.lock().unwrap();
+
+                if self.time.advance_to_next() {
+                    continue;
+                }
+            }
+
+            self.park(&root_waker, root_value.is_some());
+        }
+    }
+
+    /// Blocks `drive` until its root is woken. Workers run the tasks and turn
+    /// the reactor meanwhile; a runtime without workers relies on this thread
+    /// to wait in the reactor instead.
+    fn park(&self, root: &RootWaker, waiting_idle: bool) {
+        let recheck = waiting_idle.then_some(RECHECK_INTERVAL);
+
+        if self.injector.pool.slots() > 0 {
+            root.parker.park(recheck);
+            return;
+        }
+
+        let drive = || {
+            let timeout = match (self.time.park_timeout(), recheck) {
+                (Some(timeout), Some(recheck)) => Some(timeout.min(recheck)),
+                (timeout, recheck) => timeout.or(recheck),
+            };
+
+            Reactor::wait(&self.reactor, timeout)
+        };
+
+        let idle = &self.injector.idle;
+
+        if let Wakeup::Driven(ready) = idle.park_on(&root.parker, Some(RECHECK_INTERVAL), drive) {
+            self.reactor.lock().unwrap().dispatch(ready);
+        }
+    }
+}
+
+/// Wakes the root future of `drive`. A pending notification counts as busy work
+/// on the injector, so a paused clock is never auto-advanced while the root can
+/// still make progress.
+struct RootWaker {
+    injector: Arc<Injector>,
+    state: Mutex<RootState>,
+    /// Where `drive` sleeps while nothing can run.
+    parker: Parker,
+}
+
+struct RootState {
+    notified: bool,
+    finished: bool,
+}
+
+impl RootWaker {
+    fn new(injector: Arc<Injector>) -> Self {
+        injector.add_busy();
+
+        Self {
+            injector,
+            state: Mutex::new(RootState {
+                notified: true,
+                finished: false,
+            }),
+            parker: Parker::default(),
+        }
+    }
+
+    fn is_notified(&self) -> bool {
+        self.state.lock().unwrap().notified
+    }
+
+    /// Consumes a pending notification. The returned guard keeps the root
+    /// counted as busy until it is dropped after polling.
+    fn take(&self) -> Option<BusyGuard<'_>> {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.notified {
+            return None;
+        }
+
+        state.notified = false;
+
+        Some(self.injector.adopt_busy())
+    }
+
+    fn finish(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        state.finished = true;
+
+        if std::mem::take(&mut state.notified) {
+            drop(self.injector.adopt_busy());
+        }
+    }
+}
+
+impl Wake for RootWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.finished || state.notified {
+            return;
+        }
+
+        state.notified = true;
+        self.injector.add_busy();
+        drop(state);
+
+        self.injector.idle.unpark(&self.parker);
+    }
+}
+
+struct FinishRoot<'a>(&'a RootWaker);
+
+impl Drop for FinishRoot<'_> {
+    fn drop(&mut self) {
+        self.0.finish();
+    }
+}
+
+/// Restores the previous runtime context when dropped. Returned by [`Handle::enter`].
+pub struct EnterGuard<'a> {
+    prev_injector: Option<Arc<Injector>>,
+    prev_reactor: Option<ReactorHandle>,
+    prev_features: Option<Features>,
+    prev_blocking: Option<Arc<BlockingPool>>,
+    prev_clock: Option<Arc<Clock>>,
+    prev_time: Option<Arc<TimeDriver>>,
+    _handle: PhantomData<(&'a Handle, *const ())>,
+}
+
+impl Drop for EnterGuard<'_> {
+    fn drop(&mut self) {
+        CURRENT_INJECTOR.with(|cell| *cell.borrow_mut() = self.prev_injector.take());
+        CURRENT_REACTOR.with(|cell| *cell.borrow_mut() = self.prev_reactor.take());
+        CURRENT_FEATURES.with(|cell| *cell.borrow_mut() = self.prev_features.take());
+        CURRENT_BLOCKING.with(|cell| *cell.borrow_mut() = self.prev_blocking.take());
+        CURRENT_CLOCK.with(|cell| *cell.borrow_mut() = self.prev_clock.take());
+        CURRENT_TIME.with(|cell| *cell.borrow_mut() = self.prev_time.take());
+    }
+}
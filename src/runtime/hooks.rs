@@ -0,0 +1,49 @@
+use crate::core::task::Id;
+use crate::runtime::watchdog::Watchdog;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+pub(crate) type TaskCallback = Arc<dyn Fn(Id) + Send + Sync>;
+pub(crate) type PollEndCallback = Arc<dyn Fn(Id, Duration) + Send + Sync>;
+
+/// Instrumentation callbacks registered on `RuntimeBuilder`.
+#[derive(Clone, Default)]
+pub(crate) struct Hooks {
+    pub(crate) on_spawn: Option<TaskCallback>,
+    pub(crate) on_poll_start: Option<TaskCallback>,
+    pub(crate) on_poll_end: Option<PollEndCallback>,
+    pub(crate) on_terminate: Option<TaskCallback>,
+    pub(crate) watchdog: Option<Watchdog>,
+}
+
+impl Hooks {
+    pub(crate) fn spawn(&self, id: Id) {
+        if let Some(hook) = &self.on_spawn {
+            hook(id);
+        }
+    }
+
+    pub(crate) fn poll_start(&self, id: Id) {
+        if let Some(hook) = &self.on_poll_start {
+            hook(id);
+        }
+    }
+
+    pub(crate) fn poll_end(&self, id: Id, elapsed: Duration) {
+        if let Some(hook) = &self.on_poll_end {
+            hook(id, elapsed);
+        }
+    }
+
+    pub(crate) fn terminate(&self, id: Id) {
+        if let Some(hook) = &self.on_terminate {
+            hook(id);
+        }
+    }
+
+    /// Whether poll durations need to be measured at all.
+    pub(crate) fn times_polls(&self) -> bool {
+        self.on_poll_end.is_some() || self.watchdog.is_some()
+    }
+}
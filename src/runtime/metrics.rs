@@ -0,0 +1,177 @@
+use crate::runtime::Handle;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counters updated by the scheduler. Read through [`Handle::metrics`].
+#[derive(Default)]
+pub(crate) struct Metrics {
+    pub(crate) spawned: AtomicU64,
+    pub(crate) completed: AtomicU64,
+    pub(crate) polls: AtomicU64,
+    pub(crate) workers: Vec<WorkerMetrics>,
+}
+
+#[derive(Default)]
+pub(crate) struct WorkerMetrics {
+    pub(crate) polls: AtomicU64,
+    pub(crate) steals: AtomicU64,
+    pub(crate) parks: AtomicU64,
+    pub(crate) unparks: AtomicU64,
+}
+
+impl Metrics {
+    pub(crate) fn new(num_workers: usize) -> Self {
+        Self {
+            workers: (0..num_workers).map(|_| WorkerMetrics::default()).collect(),
+            ..Self::default()
+        }
+    }
+}
+
+pub(crate) fn incr(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Point-in-time snapshot of a runtime's scheduler and reactor counters.
+///
+/// Counters are cumulative since the runtime was built; depths and counts of
+/// live objects reflect the moment the snapshot was taken.
+#[derive(Clone, Debug, Default)]
+pub struct RuntimeMetrics {
+    workers: Vec<WorkerSnapshot>,
+    active_workers: usize,
+    injector_queue_depth: usize,
+    spawned_tasks: u64,
+    completed_tasks: u64,
+    alive_tasks: usize,
+    polls: u64,
+    reactor_turns: u64,
+    reactor_events: u64,
+    reactor_events_last_turn: usize,
+    registered_fds: usize,
+    pending_timers: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+struct WorkerSnapshot {
+    local_queue_depth: usize,
+    polls: u64,
+    steals: u64,
+    parks: u64,
+    unparks: u64,
+}
+
+impl RuntimeMetrics {
+    pub(crate) fn capture(handle: &Handle) -> Self {
+        let injector = &handle.injector;
+        let metrics = &injector.metrics;
+
+        let workers = metrics
+            .workers
+            .iter()
+            .zip(injector.locals.iter())
+            .map(|(worker, local)| WorkerSnapshot {
+                local_queue_depth: local.len(),
+                polls: worker.polls.load(Ordering::Relaxed),
+                steals: worker.steals.load(Ordering::Relaxed),
+                parks: worker.parks.load(Ordering::Relaxed),
+                unparks: worker.unparks.load(Ordering::Relaxed),
+            })
+            .collect();
+
+        let reactor = handle.reactor.lock().unwrap().stats();
+
+        Self {
+            workers,
+            active_workers: injector.pool.active(),
+            injector_queue_depth: injector.queue.total_len(),
+            spawned_tasks: metrics.spawned.load(Ordering::Relaxed),
+            completed_tasks: metrics.completed.load(Ordering::Relaxed),
+            alive_tasks: injector.alive(),
+            polls: metrics.polls.load(Ordering::Relaxed),
+            reactor_turns: reactor.turns,
+            reactor_events: reactor.events,
+            reactor_events_last_turn: reactor.events_last_turn,
+            registered_fds: reactor.registered_fds,
+            pending_timers: handle.time.len(),
+        }
+    }
+
+    /// Worker slots, i.e. the most workers that can run at once. Per-worker
+    /// accessors take a slot index.
+    pub fn num_workers(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Workers running right now, which varies with load in an elastic pool.
+    pub fn num_active_workers(&self) -> usize {
+        self.active_workers
+    }
+
+    /// Tasks waiting in the global queue, across every priority class.
+    pub fn injector_queue_depth(&self) -> usize {
+        self.injector_queue_depth
+    }
+
+    /// # Panics
+    ///
+    /// Panics when `worker >= num_workers()`, as do the other per-worker accessors.
+    pub fn worker_local_queue_depth(&self, worker: usize) -> usize {
+        self.workers[worker].local_queue_depth
+    }
+
+    pub fn worker_poll_count(&self, worker: usize) -> u64 {
+        self.workers[worker].polls
+    }
+
+    pub fn worker_steal_count(&self, worker: usize) -> u64 {
+        self.workers[worker].steals
+    }
+
+    pub fn worker_park_count(&self, worker: usize) -> u64 {
+        self.workers[worker].parks
+    }
+
+    pub fn worker_unpark_count(&self, worker: usize) -> u64 {
+        self.workers[worker].unparks
+    }
+
+    pub fn spawned_tasks_count(&self) -> u64 {
+        self.spawned_tasks
+    }
+
+    /// Tasks that ran to completion or were aborted.
+    pub fn completed_tasks_count(&self) -> u64 {
+        self.completed_tasks
+    }
+
+    pub fn alive_tasks_count(&self) -> usize {
+        self.alive_tasks
+    }
+
+    /// Task polls on every thread, including threads inside `block_on`.
+    pub fn total_poll_count(&self) -> u64 {
+        self.polls
+    }
+
+    pub fn reactor_turn_count(&self) -> u64 {
+        self.reactor_turns
+    }
+
+    pub fn reactor_event_count(&self) -> u64 {
+        self.reactor_events
+    }
+
+    /// Events handled by the most recent reactor turn that received any.
+    pub fn reactor_events_last_turn(&self) -> usize {
+        self.reactor_events_last_turn
+    }
+
+    pub fn registered_fd_count(&self) -> usize {
+        self.registered_fds
+    }
+
+    pub fn pending_timer_count(&self) -> usize {
+        self.pending_timers
+    }
+}
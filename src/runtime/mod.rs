@@ -1,11 +1,31 @@
+pub(crate) mod admission;
+pub(crate) mod blocking;
+pub(crate) mod budget;
 pub(crate) mod context;
 mod core;
+#[cfg(feature = "taskdump")]
+pub(crate) mod dump;
 pub(crate) mod executor;
+pub(crate) mod handle;
+pub(crate) mod hooks;
+pub(crate) mod metrics;
+pub(crate) mod park;
+pub(crate) mod queue;
+pub(crate) mod shutdown;
+pub(crate) mod simulation;
 pub(crate) mod waker;
+pub(crate) mod watchdog;
 pub mod workstealing;
 pub(crate) mod yield_now;
 
-pub(crate) use context::{Features, enter_context};
-pub(crate) use core::Runtime;
+pub use admission::{SpawnError, SpawnWait};
+pub(crate) use context::{Features, enter_context, enter_runtime, exit_runtime};
+pub use core::Runtime;
+#[cfg(feature = "taskdump")]
+pub use dump::{TaskDump, TaskInfo, TaskState};
 pub(crate) use executor::Executor;
+pub use handle::{EnterGuard, Handle};
+pub use metrics::RuntimeMetrics;
+pub use shutdown::ShutdownReport;
 pub(crate) use waker::make_waker;
+pub use watchdog::{Stall, StallKind};
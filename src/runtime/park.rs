@@ -0,0 +1,269 @@
+use crate::reactor::core::ReactorUnpark;
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Why [`Idle::park`] returned.
+pub(crate) enum Wakeup<R> {
+    Notified,
+    /// The worker waited in the reactor and collected these events.
+    Driven(R),
+    TimedOut,
+}
+
+/// Blocks one worker, or a thread in `block_on`, until it is unparked.
+///
+/// A thread parked as the I/O driver waits in `kevent` instead of on the
+/// condvar, so unparking it triggers the reactor's user event.
+#[derive(Default)]
+pub(crate) struct Parker {
+    state: Mutex<ParkState>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct ParkState {
+    notified: bool,
+    driving: bool,
+}
+
+impl Parker {
+    /// Returns `false` when `timeout` elapsed first.
+    pub(crate) fn park(&self, timeout: Option<Duration>) -> bool {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut state = self.state.lock().unwrap();
+
+        while !state.notified {
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+
+                    if now >= deadline {
+                        return false;
+                    }
+
+                    state = self.condvar.wait_timeout(state, deadline - now).unwrap().0;
+                }
+                None => state = self.condvar.wait(state).unwrap(),
+            }
+        }
+
+        state.notified = false;
+        true
+    }
+
+    /// Parks by running `drive`, which blocks until an I/O or timer event
+    /// arrives or the reactor is unparked.
+    fn park_driving<R>(&self, drive: impl FnOnce() -> R) -> Option<R> {
+        {
+            let mut state = self.state.lock().unwrap();
+
+            if std::mem::take(&mut state.notified) {
+                return None;
+            }
+
+            state.driving = true;
+        }
+
+        let ready = drive();
+
+        let mut state = self.state.lock().unwrap();
+        state.driving = false;
+        state.notified = false;
+
+        Some(ready)
+    }
+
+    fn unpark(&self, reactor: &ReactorUnpark) {
+        let mut state = self.state.lock().unwrap();
+        state.notified = true;
+
+        if state.driving {
+            reactor.unpark();
+        } else {
+            self.condvar.notify_one();
+        }
+    }
+
+    fn reset(&self) {
+        self.state.lock().unwrap().notified = false;
+    }
+}
+
+/// Tracks parked and searching workers so that each new task wakes at most one
+/// of them, and none while another worker is already looking for work.
+pub(crate) struct Idle {
+    parkers: Vec<Parker>,
+    /// Parked workers, most recently parked last.
+    sleepers: Mutex<Vec<usize>>,
+    /// Workers woken for new work that have not found it yet.
+    searching: AtomicUsize,
+    /// Whether a parked worker is blocked in the reactor.
+    driver: AtomicBool,
+    reactor: ReactorUnpark,
+}
+
+impl Idle {
+    pub(crate) fn new(num_workers: usize, reactor: ReactorUnpark) -> Self {
+        Self {
+            parkers: (0..num_workers).map(|_| Parker::default()).collect(),
+            sleepers: Mutex::new(Vec::with_capacity(num_workers)),
+            searching: AtomicUsize::new(0),
+            driver: AtomicBool::new(false),
+            reactor,
+        }
+    }
+
+    /// Wakes a parked worker for a task pushed to the shared queue, unless a
+    /// searching worker is going to pick it up anyway.
+    pub(crate) fn notify_one(&self) {
+        if self.searching.load(Ordering::SeqCst) > 0 {
+            return;
+        }
+
+        self.wake_sleeper(true);
+    }
+
+    /// Wakes the most recently parked worker, if any. With `unless_searching`,
+    /// gives up when another worker started searching meanwhile.
+    ///
+    /// Without workers, wakes the `block_on` thread driving the reactor, which
+    /// runs every task itself.
+    fn wake_sleeper(&self, unless_searching: bool) {
+        if self.parkers.is_empty() {
+            if self.driver.load(Ordering::Acquire) {
+                self.reactor.unpark();
+            }
+
+            return;
+        }
+
+        let worker = {
+            let mut sleepers = self.sleepers.lock().unwrap();
+
+            if unless_searching && self.searching.load(Ordering::SeqCst) > 0 {
+                return;
+            }
+
+            let Some(worker) = sleepers.pop() else {
+                return;
+            };
+
+            self.searching.fetch_add(1, Ordering::SeqCst);
+            worker
+        };
+
+        self.parkers[worker].unpark(&self.reactor);
+    }
+
+    /// Wakes `worker` for a task queued on its local queue.
+    pub(crate) fn notify_worker(&self, worker: usize) {
+        {
+            let mut sleepers = self.sleepers.lock().unwrap();
+
+            let Some(index) = sleepers.iter().position(|&w| w == worker) else {
+                // Still running: it checks its local queue before parking.
+                return;
+            };
+
+            sleepers.remove(index);
+            self.searching.fetch_add(1, Ordering::SeqCst);
+        }
+
+        self.parkers[worker].unpark(&self.reactor);
+    }
+
+    /// Called by a searching worker that found a task. The last searcher wakes
+    /// another worker when more work is queued, so that bursts spread out.
+    pub(crate) fn found_work(&self, more_queued: impl FnOnce() -> bool) {
+        if self.searching.fetch_sub(1, Ordering::SeqCst) == 1 && more_queued() {
+            self.wake_sleeper(true);
+        }
+    }
+
+    /// Called by a searching worker that found nothing and is about to park.
+    pub(crate) fn stop_searching(&self) {
+        self.searching.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Records `worker` as parked. It must check for work once more before
+    /// calling [`Idle::park`], since a task queued earlier woke nobody.
+    pub(crate) fn register(&self, worker: usize) {
+        self.sleepers.lock().unwrap().push(worker);
+    }
+
+    /// Removes `worker` from the parked set after it woke up or found work
+    /// during its last check. Returns `true` when it was woken by a
+    /// notification, and is therefore counted as searching.
+    pub(crate) fn unregister(&self, worker: usize) -> bool {
+        let mut sleepers = self.sleepers.lock().unwrap();
+
+        match sleepers.iter().position(|&w| w == worker) {
+            Some(index) => {
+                sleepers.remove(index);
+                false
+            }
+            None => {
+                self.parkers[worker].reset();
+                true
+            }
+        }
+    }
+
+    /// Blocks `worker` until it is notified or `timeout` elapses. The first
+    /// worker to park waits in the reactor with `drive` instead, without a
+    /// timeout, and returns what it collected.
+    pub(crate) fn park<R>(
+        &self,
+        worker: usize,
+        timeout: Option<Duration>,
+        drive: impl FnOnce() -> R,
+    ) -> Wakeup<R> {
+        self.park_on(&self.parkers[worker], timeout, drive)
+    }
+
+    /// Like [`Idle::park`], for a `block_on` thread of a runtime without
+    /// workers, which has to drive the reactor itself.
+    pub(crate) fn park_on<R>(
+        &self,
+        parker: &Parker,
+        timeout: Option<Duration>,
+        drive: impl FnOnce() -> R,
+    ) -> Wakeup<R> {
+        if self
+            .driver
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return if parker.park(timeout) {
+                Wakeup::Notified
+            } else {
+                Wakeup::TimedOut
+            };
+        }
+
+        let ready = parker.park_driving(drive);
+        self.driver.store(false, Ordering::Release);
+
+        // Nobody watches the reactor until another parked worker takes over.
+        self.wake_sleeper(false);
+
+        ready.map_or(Wakeup::Notified, Wakeup::Driven)
+    }
+
+    pub(crate) fn has_sleepers(&self) -> bool {
+        !self.sleepers.lock().unwrap().is_empty()
+    }
+
+    /// Wakes a thread parked on `parker`, in the reactor or on its condvar.
+    pub(crate) fn unpark(&self, parker: &Parker) {
+        parker.unpark(&self.reactor);
+    }
+
+    pub(crate) fn unpark_all(&self) {
+        for parker in &self.parkers {
+            parker.unpark(&self.reactor);
+        }
+    }
+}
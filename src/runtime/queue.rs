@@ -0,0 +1,413 @@
+//! Unbounded lock-free MPMC queue backing the shared run queue.
+//!
+//! Values live in linked blocks of `BLOCK_CAP` slots. Producers and consumers
+//! claim slots by bumping the tail and head indices with a CAS, then wait for
+//! the slot's `WRITE` bit or set its `READ` bit. Whoever reads last from a block
+//! frees it, so no garbage collection scheme is needed.
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::ops::Deref;
+use std::ptr;
+use std::sync::atomic::{self, AtomicPtr, AtomicUsize, Ordering};
+
+// Slot states.
+const WRITE: usize = 1;
+const READ: usize = 2;
+const DESTROY: usize = 4;
+
+// Each block covers one lap of indices, the last of which marks the end of the
+// block instead of holding a value.
+const LAP: usize = 32;
+pub const BLOCK_CAP: usize = LAP - 1;
+
+// Indices are shifted left by one: the low bit of the head index records that
+// the head block already has a successor.
+const SHIFT: usize = 1;
+const HAS_NEXT: usize = 1;
+
+struct Slot<T> {
+    value: UnsafeCell<MaybeUninit<T>>,
+    state: AtomicUsize,
+}
+
+impl<T> Slot<T> {
+    fn wait_write(&self) {
+        let mut backoff = Backoff::default();
+
+        while self.state.load(Ordering::Acquire) & WRITE == 0 {
+            backoff.snooze();
+        }
+    }
+}
+
+struct Block<T> {
+    next: AtomicPtr<Block<T>>,
+    slots: [Slot<T>; BLOCK_CAP],
+}
+
+impl<T> Block<T> {
+    fn new() -> Box<Self> {
+        Box::new(Self {
+            next: AtomicPtr::new(ptr::null_mut()),
+            slots: std::array::from_fn(|_| Slot {
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+                state: AtomicUsize::new(0),
+            }),
+        })
+    }
+
+    fn wait_next(&self) -> *mut Self {
+        let mut backoff = Backoff::default();
+
+        loop {
+            let next = self.next.load(Ordering::Acquire);
+
+            if !next.is_null() {
+                return next;
+            }
+
+            backoff.snooze();
+        }
+    }
+
+    /// Frees the block once every slot from `start` on has been read. A slot
+    /// still being read gets the `DESTROY` bit, and its reader carries on.
+    unsafe fn destroy(this: *mut Self, start: usize) {
+        // The last slot is skipped: its reader is the one starting at 0.
+        for i in start..BLOCK_CAP - 1 {
+            let slot = unsafe { &(*this).slots[i] };
+
+            if slot.state.load(Ordering::Acquire) & READ == 0
+                && slot.state.fetch_or(DESTROY, Ordering::AcqRel) & READ == 0
+            {
+                return;
+            }
+        }
+
+        drop(unsafe { Box::from_raw(this) });
+    }
+}
+
+struct Position<T> {
+    index: AtomicUsize,
+    block: AtomicPtr<Block<T>>,
+}
+
+pub struct SegQueue<T> {
+    head: CachePadded<Position<T>>,
+    tail: CachePadded<Position<T>>,
+}
+
+unsafe impl<T: Send> Send for SegQueue<T> {}
+unsafe impl<T: Send> Sync for SegQueue<T> {}
+
+impl<T> SegQueue<T> {
+    pub const fn new() -> Self {
+        Self {
+            head: CachePadded(Position {
+                index: AtomicUsize::new(0),
+                block: AtomicPtr::new(ptr::null_mut()),
+            }),
+            tail: CachePadded(Position {
+                index: AtomicUsize::new(0),
+                block: AtomicPtr::new(ptr::null_mut()),
+            }),
+        }
+    }
+
+    pub fn push(&self, value: T) {
+        let mut backoff = Backoff::default();
+        let mut tail = self.tail.index.load(Ordering::Acquire);
+        let mut block = self.tail.block.load(Ordering::Acquire);
+        let mut next_block = None;
+
+        loop {
+            let offset = (tail >> SHIFT) % LAP;
+
+            // Another producer is installing the next block.
+            if offset == BLOCK_CAP {
+                backoff.snooze();
+                tail = self.tail.index.load(Ordering::Acquire);
+                block = self.tail.block.load(Ordering::Acquire);
+                continue;
+            }
+
+            // Allocate the next block before claiming the last slot, so that
+            // consumers waiting on it are not held up by the allocator.
+            if offset + 1 == BLOCK_CAP && next_block.is_none() {
+                next_block = Some(Block::new());
+            }
+
+            // The very first push installs the first block.
+            if block.is_null() {
+                let new = Box::into_raw(Block::new());
+
+                if self
+                    .tail
+                    .block
+                    .compare_exchange(block, new, Ordering::Release, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    self.head.block.store(new, Ordering::Release);
+                    block = new;
+                } else {
+                    next_block = Some(unsafe { Box::from_raw(new) });
+                    tail = self.tail.index.load(Ordering::Acquire);
+                    block = self.tail.block.load(Ordering::Acquire);
+                    continue;
+                }
+            }
+
+            let new_tail = tail + (1 << SHIFT);
+
+            match self.tail.index.compare_exchange_weak(
+                tail,
+                new_tail,
+                Ordering::SeqCst,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => unsafe {
+                    if offset + 1 == BLOCK_CAP {
+                        let next_block = Box::into_raw(next_block.unwrap());
+                        let next_index = new_tail.wrapping_add(1 << SHIFT);
+
+                        self.tail.block.store(next_block, Ordering::Release);
+                        self.tail.index.store(next_index, Ordering::Release);
+                        (*block).next.store(next_block, Ordering::Release);
+                    }
+
+                    let slot = &(*block).slots[offset];
+                    slot.value.get().write(MaybeUninit::new(value));
+                    slot.state.fetch_or(WRITE, Ordering::Release);
+
+                    return;
+                },
+                Err(current) => {
+                    tail = current;
+                    block = self.tail.block.load(Ordering::Acquire);
+                    backoff.spin();
+                }
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let mut value = None;
+        self.pop_batch(1, |v| value = Some(v));
+
+        value
+    }
+
+    /// Pops up to `max` values with a single claim on the head, passing them to
+    /// `sink` in queue order. A batch never spans two blocks. Returns how many
+    /// values were popped.
+    pub fn pop_batch(&self, max: usize, mut sink: impl FnMut(T)) -> usize {
+        debug_assert!(max > 0);
+
+        let mut backoff = Backoff::default();
+        let mut head = self.head.index.load(Ordering::Acquire);
+        let mut block = self.head.block.load(Ordering::Acquire);
+
+        loop {
+            let offset = (head >> SHIFT) % LAP;
+
+            // Another consumer is moving the head to the next block.
+            if offset == BLOCK_CAP {
+                backoff.snooze();
+                head = self.head.index.load(Ordering::Acquire);
+                block = self.head.block.load(Ordering::Acquire);
+                continue;
+            }
+
+            // Values are claimable up to the end of the block, or up to the
+            // tail when it is still in the same block.
+            let mut available = BLOCK_CAP - offset;
+            let mut has_next = head & HAS_NEXT != 0;
+
+            if !has_next {
+                atomic::fence(Ordering::SeqCst);
+                let tail = self.tail.index.load(Ordering::Relaxed);
+
+                if head >> SHIFT == tail >> SHIFT {
+                    return 0;
+                }
+
+                if (head >> SHIFT) / LAP == (tail >> SHIFT) / LAP {
+                    available = (tail >> SHIFT) - (head >> SHIFT);
+                } else {
+                    has_next = true;
+                }
+            }
+
+            // The first block is still being installed.
+            if block.is_null() {
+                backoff.snooze();
+                head = self.head.index.load(Ordering::Acquire);
+                block = self.head.block.load(Ordering::Acquire);
+                continue;
+            }
+
+            let count = available.min(max);
+            let mut new_head = (head & !HAS_NEXT) + (count << SHIFT);
+
+            if has_next {
+                new_head |= HAS_NEXT;
+            }
+
+            match self.head.index.compare_exchange_weak(
+                head,
+                new_head,
+                Ordering::SeqCst,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => unsafe {
+                    let end = offset + count;
+
+                    if end == BLOCK_CAP {
+                        let next = (*block).wait_next();
+                        let mut next_index = (new_head & !HAS_NEXT).wrapping_add(1 << SHIFT);
+
+                        if !(*next).next.load(Ordering::Relaxed).is_null() {
+                            next_index |= HAS_NEXT;
+                        }
+
+                        self.head.block.store(next, Ordering::Release);
+                        self.head.index.store(next_index, Ordering::Release);
+                    }
+
+                    for i in offset..end {
+                        let slot = &(*block).slots[i];
+                        slot.wait_write();
+                        let value = slot.value.get().read().assume_init();
+
+                        if i + 1 == BLOCK_CAP {
+                            Block::destroy(block, 0);
+                        } else if slot.state.fetch_or(READ, Ordering::AcqRel) & DESTROY != 0 {
+                            Block::destroy(block, i + 1);
+                        }
+
+                        sink(value);
+                    }
+
+                    return count;
+                },
+                Err(current) => {
+                    head = current;
+                    block = self.head.block.load(Ordering::Acquire);
+                    backoff.spin();
+                }
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let head = self.head.index.load(Ordering::SeqCst);
+        let tail = self.tail.index.load(Ordering::SeqCst);
+
+        head >> SHIFT == tail >> SHIFT
+    }
+
+    /// Number of queued values, exact only while no other thread uses the queue.
+    pub fn len(&self) -> usize {
+        loop {
+            let mut tail = self.tail.index.load(Ordering::SeqCst);
+            let mut head = self.head.index.load(Ordering::SeqCst);
+
+            // Retry until the tail did not move while the head was read.
+            if self.tail.index.load(Ordering::SeqCst) != tail {
+                continue;
+            }
+
+            tail &= !((1 << SHIFT) - 1);
+            head &= !((1 << SHIFT) - 1);
+
+            // An index on a block's end marker counts as the next block's start.
+            if (tail >> SHIFT) & (LAP - 1) == LAP - 1 {
+                tail = tail.wrapping_add(1 << SHIFT);
+            }
+            if (head >> SHIFT) & (LAP - 1) == LAP - 1 {
+                head = head.wrapping_add(1 << SHIFT);
+            }
+
+            // Rebase both indices on the head's lap, then skip one end marker per lap.
+            let lap = (head >> SHIFT) / LAP;
+            tail = tail.wrapping_sub((lap * LAP) << SHIFT) >> SHIFT;
+            head = head.wrapping_sub((lap * LAP) << SHIFT) >> SHIFT;
+
+            return tail - head - tail / LAP;
+        }
+    }
+}
+
+impl<T> Default for SegQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for SegQueue<T> {
+    fn drop(&mut self) {
+        let mut head = *self.head.0.index.get_mut() & !((1 << SHIFT) - 1);
+        let tail = *self.tail.0.index.get_mut() & !((1 << SHIFT) - 1);
+        let mut block = *self.head.0.block.get_mut();
+
+        unsafe {
+            while head != tail {
+                let offset = (head >> SHIFT) % LAP;
+
+                if offset < BLOCK_CAP {
+                    (*(*block).slots[offset].value.get()).assume_init_drop();
+                } else {
+                    let next = *(*block).next.get_mut();
+                    drop(Box::from_raw(block));
+                    block = next;
+                }
+
+                head = head.wrapping_add(1 << SHIFT);
+            }
+
+            if !block.is_null() {
+                drop(Box::from_raw(block));
+            }
+        }
+    }
+}
+
+/// Keeps the head and tail on separate cache lines.
+#[repr(align(128))]
+struct CachePadded<T>(T);
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Spins with exponential backoff, then yields the thread.
+#[derive(Default)]
+struct Backoff {
+    step: u32,
+}
+
+impl Backoff {
+    const SPIN_LIMIT: u32 = 6;
+
+    fn spin(&mut self) {
+        for _ in 0..1 << self.step.min(Self::SPIN_LIMIT) {
+            std::hint::spin_loop();
+        }
+
+        self.step += 1;
+    }
+
+    fn snooze(&mut self) {
+        if self.step <= Self::SPIN_LIMIT {
+            self.spin();
+        } else {
+            std::thread::yield_now();
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::core::task::Id;
+
+/// Outcome of a runtime shutdown.
+#[derive(Debug, Default, Clone)]
+pub struct ShutdownReport {
+    pub(crate) cancelled: Vec<Id>,
+    pub(crate) running: Vec<Id>,
+    pub(crate) workers_joined: bool,
+}
+
+impl ShutdownReport {
+    /// Tasks that were still pending and whose futures were dropped.
+    pub fn cancelled(&self) -> &[Id] {
+        &self.cancelled
+    }
+
+    /// Tasks that were still being polled by a worker when the timeout elapsed.
+    /// Their futures could not be dropped and are left to the detached worker.
+    pub fn still_running(&self) -> &[Id] {
+        &self.running
+    }
+
+    pub fn workers_joined(&self) -> bool {
+        self.workers_joined
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.workers_joined && self.running.is_empty()
+    }
+}
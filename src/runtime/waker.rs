@@ -1,7 +1,6 @@
 use crate::Task;
 
 use std::sync::Arc;
-use std::sync::atomic::Ordering;
 use std::task::{RawWaker, RawWakerVTable, Waker};
 
 pub(crate) struct TaskWaker<T: Send + Sync + 'static> {
@@ -14,9 +13,7 @@ impl<T: Send + Sync + 'static> TaskWaker<T> {
     }
 
     fn wake(self: &Arc<Self>) {
-        if !self.task.inqueue.swap(true, Ordering::AcqRel) {
-            self.task.injector.reschedule(self.task.clone());
-        }
+        self.task.schedule();
     }
 
     fn clone_raw(data_ptr: *const ()) -> RawWaker {
@@ -0,0 +1,173 @@
+use crate::core::task::{Id, Runnable};
+use crate::runtime::workstealing::Injector;
+
+use std::fmt;
+use std::panic::Location;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+pub(crate) type StallCallback = Arc<dyn Fn(&Stall) + Send + Sync>;
+
+/// Stall detection settings registered on `RuntimeBuilder`.
+#[derive(Clone)]
+pub(crate) struct Watchdog {
+    pub(crate) threshold: Duration,
+    pub(crate) on_stall: StallCallback,
+}
+
+impl Watchdog {
+    /// Reports a poll that has already returned but took too long.
+    pub(crate) fn check_poll(&self, task: &dyn Runnable, elapsed: Duration) {
+        if elapsed >= self.threshold {
+            (self.on_stall)(&Stall::new(StallKind::SlowPoll, task, elapsed));
+        }
+    }
+}
+
+struct Activity {
+    started: Instant,
+    task: Arc<dyn Runnable>,
+    reported: bool,
+}
+
+/// The task a worker is polling right now, tracked only when a watchdog is set.
+#[derive(Default)]
+pub(crate) struct WorkerActivity(Mutex<Option<Activity>>);
+
+impl WorkerActivity {
+    pub(crate) fn enter(&self, task: &Arc<dyn Runnable>) {
+        *self.0.lock().unwrap() = Some(Activity {
+            started: Instant::now(),
+            task: task.clone(),
+            reported: false,
+        });
+    }
+
+    pub(crate) fn leave(&self) {
+        self.0.lock().unwrap().take();
+    }
+
+    /// Flags the current poll once it has run past `threshold`.
+    fn check(&self, worker: usize, threshold: Duration) -> Option<Stall> {
+        let mut activity = self.0.lock().unwrap();
+        let activity = activity.as_mut()?;
+        let elapsed = activity.started.elapsed();
+
+        if activity.reported || elapsed < threshold {
+            return None;
+        }
+
+        activity.reported = true;
+
+        Some(Stall::new(
+            StallKind::StuckWorker { worker },
+            &*activity.task,
+            elapsed,
+        ))
+    }
+}
+
+/// Starts the thread that looks for workers stuck inside a single poll. It
+/// exits once the runtime shuts down.
+pub(crate) fn spawn(injector: &Arc<Injector>, watchdog: Watchdog) {
+    let injector = Arc::downgrade(injector);
+    let interval = (watchdog.threshold / 4).max(Duration::from_millis(1));
+
+    std::thread::Builder::new()
+        .name("cadentis-watchdog".into())
+        .spawn(move || watch(injector, watchdog, interval))
+        .expect("failed to spawn the watchdog thread");
+}
+
+fn watch(injector: Weak<Injector>, watchdog: Watchdog, interval: Duration) {
+    loop {
+        std::thread::sleep(interval);
+
+        let Some(injector) = injector.upgrade() else {
+            return;
+        };
+
+        if injector.is_shutdown() {
+            return;
+        }
+
+        for (worker, activity) in injector.activity.iter().enumerate() {
+            if let Some(stall) = activity.check(worker, watchdog.threshold) {
+                (watchdog.on_stall)(&stall);
+            }
+        }
+    }
+}
+
+/// Why a [`Stall`] was reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StallKind {
+    /// A poll returned after running longer than the threshold.
+    SlowPoll,
+    /// A worker has been inside the same poll for longer than the threshold and
+    /// has not returned to the scheduler yet.
+    StuckWorker { worker: usize },
+}
+
+/// A poll that exceeded the watchdog threshold.
+#[derive(Clone, Debug)]
+pub struct Stall {
+    kind: StallKind,
+    task: Id,
+    name: Option<String>,
+    location: &'static Location<'static>,
+    duration: Duration,
+}
+
+impl Stall {
+    fn new(kind: StallKind, task: &dyn Runnable, duration: Duration) -> Self {
+        Self {
+            kind,
+            task: task.id(),
+            name: task.name().map(str::to_owned),
+            location: task.location(),
+            duration,
+        }
+    }
+
+    pub fn kind(&self) -> StallKind {
+        self.kind
+    }
+
+    pub fn task_id(&self) -> Id {
+        self.task
+    }
+
+    pub fn task_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Where the task was spawned.
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// How long the poll ran, or has been running for a stuck worker.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+impl fmt::Display for Stall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "task {}", self.task)?;
+
+        if let Some(name) = &self.name {
+            write!(f, " ({})", name)?;
+        }
+
+        match self.kind {
+            StallKind::SlowPoll => write!(f, " was polled for {:?}", self.duration)?,
+            StallKind::StuckWorker { worker } => {
+                write!(f, " has blocked worker {} for {:?}", worker, self.duration)?
+            }
+        }
+
+        write!(f, ", spawned at {}", self.location)
+    }
+}
@@ -1,66 +1,478 @@
-use crate::core::task::Runnable;
-use crate::reactor::core::ReactorHandle;
-use crate::runtime::context::{CURRENT_FEATURES, CURRENT_REACTOR, Features};
+use crate::core::task::{Id, Priority, Runnable};
+use crate::reactor::core::{Reactor, ReactorUnpark};
+use crate::runtime::admission::CapacityWaiters;
+use crate::runtime::executor::{self, Pool};
+use crate::runtime::hooks::Hooks;
+use crate::runtime::metrics::{self, Metrics};
+use crate::runtime::park::{Idle, Wakeup};
+use crate::runtime::queue::SegQueue;
+use crate::runtime::simulation::Rng;
+use crate::runtime::watchdog::WorkerActivity;
+use crate::runtime::{Handle, enter_runtime};
 
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, Condvar, Mutex};
+use std::sync::{Arc, Mutex};
+
+/// One lock-free FIFO per priority class. Pops follow a fixed smooth weighted
+/// round-robin schedule, falling back to the other classes in priority order
+/// when the scheduled one is empty, so lower classes are slowed down but never
+/// starved.
+///
+/// In simulation mode the tasks sit in `simulated` instead, where a seeded RNG
+/// picks any queued task, so that every seed explores a different but
+/// reproducible interleaving.
+pub(crate) struct RunQueues {
+    queues: [SegQueue<Arc<dyn Runnable>>; 3],
+    /// Pops so far, indexing into `SCHEDULE`.
+    tick: AtomicUsize,
+    simulated: Option<Mutex<Simulated>>,
+}
+
+/// Simulation runs on a single thread, so a locked deque per class costs
+/// nothing and lets a pick remove from the middle.
+struct Simulated {
+    rng: Rng,
+    queues: [VecDeque<Arc<dyn Runnable>>; 3],
+}
+
+impl Simulated {
+    /// Takes the queued task at a random position, keeping the order of the rest.
+    fn pop_random(&mut self) -> Option<Arc<dyn Runnable>> {
+        let len = self.queues.iter().map(VecDeque::len).sum();
+
+        if len == 0 {
+            return None;
+        }
+
+        let mut index = self.rng.below(len);
+
+        for queue in &mut self.queues {
+            if index < queue.len() {
+                return queue.remove(index);
+            }
+
+            index -= queue.len();
+        }
+
+        None
+    }
+}
+
+const ROUND: usize =
+    (Priority::High.weight() + Priority::Normal.weight() + Priority::Background.weight()) as usize;
+
+/// One round of smooth weighted round-robin: every class earns its weight in
+/// credit per slot and the richest class takes the slot.
+const SCHEDULE: [Priority; ROUND] = {
+    let mut schedule = [Priority::High; ROUND];
+    let mut credits = [0; 3];
+    let mut slot = 0;
+
+    while slot < ROUND {
+        let mut best = 0;
+        let mut i = 0;
+
+        while i < 3 {
+            credits[i] += Priority::ALL[i].weight();
+
+            if credits[i] > credits[best] {
+                best = i;
+            }
+
+            i += 1;
+        }
+
+        credits[best] -= ROUND as i64;
+        schedule[slot] = Priority::ALL[best];
+        slot += 1;
+    }
+
+    schedule
+};
+
+impl RunQueues {
+    fn new(seed: Option<u64>) -> Self {
+        Self {
+            queues: Default::default(),
+            tick: AtomicUsize::new(0),
+            simulated: seed.map(|seed| {
+                Mutex::new(Simulated {
+                    rng: Rng::new(seed),
+                    queues: Default::default(),
+                })
+            }),
+        }
+    }
+
+    pub(crate) fn push(&self, task: Arc<dyn Runnable>) {
+        let index = task.priority().index();
+
+        match &self.simulated {
+            Some(simulated) => simulated.lock().unwrap().queues[index].push_back(task),
+            None => self.queues[index].push(task),
+        }
+    }
+
+    pub(crate) fn pop(&self) -> Option<Arc<dyn Runnable>> {
+        let mut task = None;
+        self.pop_batch(1, |t| task = Some(t));
+
+        task
+    }
+
+    /// Pops up to `max` tasks of a single class, passing them to `sink` in
+    /// queue order. Returns how many were popped.
+    pub(crate) fn pop_batch(&self, max: usize, mut sink: impl FnMut(Arc<dyn Runnable>)) -> usize {
+        if let Some(simulated) = &self.simulated {
+            return match simulated.lock().unwrap().pop_random() {
+                Some(task) => {
+                    sink(task);
+                    1
+                }
+                None => 0,
+            };
+        }
+
+        let scheduled = SCHEDULE[self.tick.fetch_add(1, Ordering::Relaxed) % SCHEDULE.len()];
+
+        std::iter::once(scheduled)
+            .chain(Priority::ALL.into_iter().filter(|&p| p != scheduled))
+            .map(|priority| self.queues[priority.index()].pop_batch(max, &mut sink))
+            .find(|&popped| popped > 0)
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn len(&self, priority: Priority) -> usize {
+        match &self.simulated {
+            Some(simulated) => simulated.lock().unwrap().queues[priority.index()].len(),
+            None => self.queues[priority.index()].len(),
+        }
+    }
+
+    pub(crate) fn total_len(&self) -> usize {
+        Priority::ALL.into_iter().map(|p| self.len(p)).sum()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        match &self.simulated {
+            Some(simulated) => simulated
+                .lock()
+                .unwrap()
+                .queues
+                .iter()
+                .all(VecDeque::is_empty),
+            None => self.queues.iter().all(SegQueue::is_empty),
+        }
+    }
+}
 
 pub(crate) struct Injector {
-    pub(crate) queue: Mutex<VecDeque<Arc<dyn Runnable>>>,
-    pub(crate) condvar: Condvar,
+    pub(crate) queue: RunQueues,
+    pub(crate) idle: Idle,
+    pub(crate) pool: Pool,
+    pub(crate) metrics: Metrics,
+    pub(crate) hooks: Hooks,
+    pub(crate) locals: Arc<Vec<LocalQueue>>,
+    /// One slot per worker when a watchdog is configured, empty otherwise.
+    pub(crate) activity: Vec<WorkerActivity>,
+    /// Spawns from a worker are homed on that worker.
+    pub(crate) thread_per_core: bool,
 
+    /// Spawners waiting for `active` to drop below `max_tasks`.
+    pub(crate) capacity: CapacityWaiters,
+
+    tasks: TaskRegistry,
+
+    /// Live tasks, including those admitted but not pushed yet.
     active: AtomicUsize,
+    max_tasks: Option<usize>,
+    /// Queued tasks plus threads polling or searching for one. Nothing can run
+    /// while it is zero.
+    busy: AtomicUsize,
     shutdown: AtomicBool,
+    closed: AtomicBool,
 }
 
 impl Injector {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(
+        seed: Option<u64>,
+        pool: Pool,
+        thread_per_core: bool,
+        max_tasks: Option<usize>,
+        hooks: Hooks,
+        reactor: ReactorUnpark,
+    ) -> Self {
+        let num_workers = pool.slots();
+        let watched = if hooks.watchdog.is_some() {
+            num_workers
+        } else {
+            0
+        };
+
         Self {
-            queue: Mutex::new(VecDeque::new()),
-            condvar: Condvar::new(),
+            queue: RunQueues::new(seed),
+            idle: Idle::new(num_workers, reactor),
+            pool,
+            metrics: Metrics::new(num_workers),
+            hooks,
+            locals: Arc::new((0..num_workers).map(|_| LocalQueue::new()).collect()),
+            activity: (0..watched).map(|_| WorkerActivity::default()).collect(),
+            thread_per_core,
+            capacity: CapacityWaiters::default(),
+            tasks: TaskRegistry::default(),
             active: AtomicUsize::new(0),
+            max_tasks,
+            busy: AtomicUsize::new(0),
             shutdown: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
         }
     }
 
     pub(crate) fn shutdown(&self) {
         self.shutdown.store(true, Ordering::Release);
-        self.condvar.notify_all();
+        self.idle.unpark_all();
     }
 
     pub(crate) fn is_shutdown(&self) -> bool {
         self.shutdown.load(Ordering::Acquire)
     }
 
+    pub(crate) fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.shutdown();
+        self.capacity.wake_all();
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// Counts one more live task, ignoring the task limit.
+    pub(crate) fn admit(&self) {
+        self.active.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Counts one more live task unless that would exceed the task limit.
+    pub(crate) fn try_admit(&self) -> bool {
+        let Some(max) = self.max_tasks else {
+            self.admit();
+            return true;
+        };
+
+        self.active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                (active < max).then_some(active + 1)
+            })
+            .is_ok()
+    }
+
+    /// Gives back a slot taken by `admit` or `try_admit`.
+    fn release(&self) {
+        self.active.fetch_sub(1, Ordering::AcqRel);
+
+        if self.max_tasks.is_some() {
+            self.capacity.wake_one();
+        }
+    }
+
+    /// Queues a new task, which must already be admitted.
     pub(crate) fn push(&self, task: Arc<dyn Runnable>) {
-        self.active.fetch_add(1, Ordering::Relaxed);
+        if self.is_closed() {
+            task.cancel();
+            self.release();
+            return;
+        }
 
-        let mut queue = self.queue.lock().unwrap();
-        queue.push_back(task);
+        metrics::incr(&self.metrics.spawned);
+        self.tasks.insert(task.clone());
+        self.hooks.spawn(task.id());
 
-        self.condvar.notify_one();
+        self.enqueue(task);
     }
 
     pub(crate) fn reschedule(&self, task: Arc<dyn Runnable>) {
-        let mut queue = self.queue.lock().unwrap();
-        queue.push_back(task);
+        if self.is_closed() {
+            return;
+        }
+
+        self.enqueue(task);
+    }
+
+    /// Queues a task on its home worker, or on the shared queue when it has none.
+    fn enqueue(&self, task: Arc<dyn Runnable>) {
+        self.add_busy();
+
+        // A task homed on a retired worker of an elastic pool runs anywhere.
+        let task = match task.home() {
+            Some(home) => match self.locals[home].push_if(task, || self.pool.is_running(home)) {
+                Ok(()) => return self.idle.notify_worker(home),
+                Err(task) => task,
+            },
+            None => task,
+        };
+
+        self.queue.push(task);
+        self.idle.notify_one();
+    }
+
+    /// Moves tasks taken off a local queue to the shared queue.
+    fn requeue(&self, tasks: impl IntoIterator<Item = Arc<dyn Runnable>>) {
+        let mut moved = false;
+
+        for task in tasks {
+            self.queue.push(task);
+            moved = true;
+        }
 
-        self.condvar.notify_one();
+        if moved {
+            self.idle.notify_one();
+        }
     }
 
+    /// Pops a task to poll. The caller must be counted as busy.
     pub(crate) fn pop(&self) -> Option<Arc<dyn Runnable>> {
-        self.queue.lock().unwrap().pop_front()
+        self.queue.pop().inspect(|_| self.take_queued(1))
+    }
+
+    /// Stops counting `n` tasks that left the queues.
+    fn take_queued(&self, n: usize) {
+        self.busy.fetch_sub(n, Ordering::AcqRel);
+    }
+
+    /// Runs `f` while counting the calling thread as busy polling a task.
+    pub(crate) fn run_busy<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.add_busy();
+        let _busy = self.adopt_busy();
+
+        f()
+    }
+
+    /// Records a unit of work that can run: a queued task, a thread polling, or
+    /// a pending wakeup of the root future.
+    pub(crate) fn add_busy(&self) {
+        self.busy.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Takes ownership of a unit recorded with `add_busy`, releasing it on drop.
+    pub(crate) fn adopt_busy(&self) -> BusyGuard<'_> {
+        BusyGuard(&self.busy)
     }
 
-    pub(crate) fn task_completed(&self) {
-        self.active.fetch_sub(1, Ordering::Release);
-        self.condvar.notify_all();
+    /// Whether no task is queued or being polled, i.e. nothing can make
+    /// progress until a timer fires or an I/O event arrives.
+    ///
+    /// Queued tasks count as busy from just before they are pushed until after
+    /// they are popped by a thread that is itself busy, so a single load sees
+    /// every queue and every poller consistently.
+    pub(crate) fn is_quiescent(&self) -> bool {
+        self.busy.load(Ordering::Acquire) == 0
+    }
+
+    pub(crate) fn task_completed(&self, id: Id) {
+        self.tasks.remove(id);
+
+        metrics::incr(&self.metrics.completed);
+        self.hooks.terminate(id);
+        self.release();
     }
 
     pub(crate) fn is_idle(&self) -> bool {
-        self.active.load(Ordering::Acquire) == 0
+        self.alive() == 0
+    }
+
+    pub(crate) fn alive(&self) -> usize {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Live tasks, ordered by ID.
+    #[cfg(feature = "taskdump")]
+    pub(crate) fn live_tasks(&self) -> Vec<Arc<dyn Runnable>> {
+        self.tasks.snapshot()
+    }
+
+    pub(crate) fn drain(&self) -> Vec<Arc<dyn Runnable>> {
+        let mut queued = 0;
+
+        while self.queue.pop().is_some() {
+            queued += 1;
+        }
+
+        for local in self.locals.iter() {
+            queued += local.take_all().len();
+        }
+
+        self.take_queued(queued);
+
+        self.tasks.take_all()
+    }
+}
+
+/// Every live task by ID, split into shards so that concurrent spawns and
+/// completions rarely contend on the same lock.
+struct TaskRegistry {
+    shards: [Mutex<HashMap<Id, Arc<dyn Runnable>>>; REGISTRY_SHARDS],
+}
+
+/// IDs are handed out in sequence, so consecutive tasks land on different shards.
+const REGISTRY_SHARDS: usize = 64;
+
+impl Default for TaskRegistry {
+    fn default() -> Self {
+        Self {
+            shards: std::array::from_fn(|_| Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl TaskRegistry {
+    fn shard(&self, id: Id) -> &Mutex<HashMap<Id, Arc<dyn Runnable>>> {
+        &self.shards[id.as_u64() as usize % REGISTRY_SHARDS]
+    }
+
+    fn insert(&self, task: Arc<dyn Runnable>) {
+        self.shard(task.id())
+            .lock()
+            .unwrap()
+            .insert(task.id(), task);
+    }
+
+    fn remove(&self, id: Id) {
+        self.shard(id).lock().unwrap().remove(&id);
+    }
+
+    /// Live tasks, ordered by ID.
+    #[cfg(feature = "taskdump")]
+    fn snapshot(&self) -> Vec<Arc<dyn Runnable>> {
+        self.collect(|tasks| tasks.values().cloned().collect())
+    }
+
+    /// Removes and returns every live task, ordered by ID.
+    fn take_all(&self) -> Vec<Arc<dyn Runnable>> {
+        self.collect(|tasks| std::mem::take(tasks).into_values().collect())
+    }
+
+    fn collect(
+        &self,
+        mut f: impl FnMut(&mut HashMap<Id, Arc<dyn Runnable>>) -> Vec<Arc<dyn Runnable>>,
+    ) -> Vec<Arc<dyn Runnable>> {
+        let mut live: Vec<_> = self
+            .shards
+            .iter()
+            .flat_map(|shard| f(&mut shard.lock().unwrap()))
+            .collect();
+        live.sort_by_key(|t| t.id());
+
+        live
+    }
+}
+
+pub(crate) struct BusyGuard<'a>(&'a AtomicUsize);
+
+impl Drop for BusyGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
     }
 }
 
@@ -75,63 +487,262 @@ impl LocalQueue {
         }
     }
 
-    // pub fn push(&self, task: Arc<dyn Runnable>) {
-    //     self.deque.lock().unwrap().push_back(task);
-    // }
+    /// Pushes `task` unless `accept`, checked under the queue lock, says no.
+    /// It goes behind every queued task, like the tasks of `push_batch`, so a
+    /// task that keeps waking itself cannot starve the others.
+    fn push_if(
+        &self,
+        task: Arc<dyn Runnable>,
+        accept: impl FnOnce() -> bool,
+    ) -> Result<(), Arc<dyn Runnable>> {
+        let mut deque = self.deque.lock().unwrap();
+
+        if !accept() {
+            return Err(task);
+        }
+
+        deque.push_front(task);
+        Ok(())
+    }
 
     pub(crate) fn pop(&self) -> Option<Arc<dyn Runnable>> {
         self.deque.lock().unwrap().pop_back()
     }
 
+    pub(crate) fn len(&self) -> usize {
+        self.deque.lock().unwrap().len()
+    }
+
+    /// Takes the oldest task that is not homed on this worker.
     pub(crate) fn steal(&self) -> Option<Arc<dyn Runnable>> {
-        self.deque.lock().unwrap().pop_front()
+        let mut deque = self.deque.lock().unwrap();
+        let index = deque.iter().position(|task| task.home().is_none())?;
+
+        deque.remove(index)
+    }
+
+    /// Queues a batch taken from the shared queue so that `pop` returns it in
+    /// order.
+    fn push_batch(&self, tasks: impl DoubleEndedIterator<Item = Arc<dyn Runnable>>) {
+        self.deque.lock().unwrap().extend(tasks.rev());
+    }
+
+    fn take_all(&self) -> VecDeque<Arc<dyn Runnable>> {
+        std::mem::take(&mut *self.deque.lock().unwrap())
+    }
+
+    /// Takes every task if `release`, checked under the queue lock, says so.
+    /// No task can be pushed in between.
+    fn take_all_if(&self, release: impl FnOnce() -> bool) -> Option<VecDeque<Arc<dyn Runnable>>> {
+        let mut deque = self.deque.lock().unwrap();
+
+        release().then(|| std::mem::take(&mut *deque))
     }
 }
 
 pub(crate) struct Worker {
     pub(crate) id: usize,
+    /// Core the worker is pinned to in thread-per-core mode.
+    pub(crate) core: Option<usize>,
     pub(crate) locals: Arc<Vec<LocalQueue>>,
     pub(crate) injector: Arc<Injector>,
-    pub(crate) reactor: ReactorHandle,
-    pub(crate) features: Features,
+    pub(crate) handle: Handle,
 }
 
 impl Worker {
-    pub fn run(&self) {
-        CURRENT_INJECTOR.with(|cell| {
-            *cell.borrow_mut() = Some(self.injector.clone());
-        });
-        CURRENT_REACTOR.with(|cell| {
-            *cell.borrow_mut() = Some(self.reactor.clone());
-        });
-        CURRENT_FEATURES.with(|cell| {
-            *cell.borrow_mut() = Some(self.features);
+    pub fn run(self: Arc<Self>) {
+        let _runtime = enter_runtime();
+        let _guard = self.handle.enter();
+
+        if let Some(core) = self.core {
+            executor::pin_current_thread(core);
+        }
+
+        CURRENT_WORKER.with(|cell| {
+            *cell.borrow_mut() = Some(self.clone());
         });
 
+        // Whether the worker was woken for new work and has not found it yet.
+        let mut searching = false;
+        let mut polls: u32 = 0;
+
         loop {
             if self.injector.is_shutdown() {
                 return;
             }
 
-            if let Some(task) = self.locals[self.id].pop() {
-                task.poll();
-            } else if let Some(task) = self.injector.pop() {
-                task.poll();
-            } else if let Some(task) = self.try_steal() {
-                task.poll();
-            } else {
-                let queue = self.injector.queue.lock().unwrap();
-
-                if queue.is_empty() && !self.injector.is_shutdown() {
-                    let _ = self
-                        .injector
-                        .condvar
-                        .wait_timeout(queue, std::time::Duration::from_millis(10));
+            // Count as busy while searching too, so that a popped task is never
+            // invisible to the auto-advance check.
+            let ran = self.injector.run_busy(|| {
+                self.next_task()
+                    .map(|task| {
+                        if std::mem::take(&mut searching) {
+                            self.injector.idle.found_work(|| self.has_shared_work());
+                        }
+
+                        self.maybe_grow();
+
+                        metrics::incr(&self.metrics().polls);
+                        self.poll(task)
+                    })
+                    .is_some()
+            });
+
+            if ran {
+                // Nothing else turns the driver while every worker is busy.
+                polls = polls.wrapping_add(1);
+
+                if polls.is_multiple_of(EVENT_INTERVAL) {
+                    self.turn_driver();
                 }
+
+                continue;
+            }
+
+            self.turn_driver();
+
+            if std::mem::take(&mut searching) {
+                self.injector.idle.stop_searching();
+            }
+
+            match self.park() {
+                Some(woken) => searching = woken,
+                None => return,
+            }
+        }
+    }
+
+    /// Polls the reactor without blocking and fires due timers, unless another
+    /// thread holds the reactor.
+    fn turn_driver(&self) {
+        if let Ok(mut reactor) = self.handle.reactor.try_lock() {
+            reactor.poll_events();
+            reactor.wake_ready();
+            self.handle.time.process();
+
+            if self.handle.is_quiescent() {
+                self.handle.time.advance_to_next();
+            }
+        }
+    }
+
+    /// Parks until notified, or until the reactor has events when this worker
+    /// is the one waiting on it. Returns whether it was woken for new work, or
+    /// `None` when the worker retired from an elastic pool.
+    fn park(&self) -> Option<bool> {
+        let idle = &self.injector.idle;
+        let mut timed_out = false;
+
+        // Register first: a task queued after the check below wakes this worker.
+        idle.register(self.id);
+
+        if !self.has_work() && !self.injector.is_shutdown() {
+            metrics::incr(&self.metrics().parks);
+
+            let keep_alive = self.injector.pool.keep_alive();
+
+            let drive = || Reactor::wait(&self.handle.reactor, self.handle.time.park_timeout());
+
+            match idle.park(self.id, keep_alive, drive) {
+                Wakeup::Driven(ready) => self.handle.reactor.lock().unwrap().dispatch(ready),
+                Wakeup::TimedOut => timed_out = true,
+                Wakeup::Notified => {}
+            }
+
+            metrics::incr(&self.metrics().unparks);
+        }
+
+        let woken = idle.unregister(self.id);
+
+        if timed_out && !woken && self.retire() {
+            return None;
+        }
+
+        Some(woken)
+    }
+
+    /// Takes the next task to poll: from the local queue, then a batch from the
+    /// shared queue, then one stolen from another worker.
+    fn next_task(&self) -> Option<Arc<dyn Runnable>> {
+        let task = self.locals[self.id]
+            .pop()
+            .or_else(|| self.pop_shared())
+            .or_else(|| self.try_steal())?;
+
+        self.injector.take_queued(1);
+        Some(task)
+    }
+
+    /// Pops the next task from the shared queue, along with up to a fair share
+    /// of the tasks behind it, which go to the local queue.
+    fn pop_shared(&self) -> Option<Arc<dyn Runnable>> {
+        let queue = &self.injector.queue;
+        let share = queue.total_len() / self.injector.pool.active().max(1);
+        let mut batch = Vec::new();
+
+        queue.pop_batch(share.clamp(1, MAX_BATCH), |task| batch.push(task));
+
+        let mut batch = batch.into_iter();
+        let first = batch.next()?;
+        self.locals[self.id].push_batch(batch);
+
+        Some(first)
+    }
+
+    /// Gives the slot back to an elastic pool, moving the tasks queued on this
+    /// worker to the shared queue.
+    fn retire(&self) -> bool {
+        let pool = &self.injector.pool;
+
+        let Some(tasks) = self.locals[self.id].take_all_if(|| pool.retire(self.id)) else {
+            return false;
+        };
+
+        self.injector.requeue(tasks);
+        true
+    }
+
+    /// Starts one more worker in an elastic pool when the shared queue keeps
+    /// more tasks than the running workers and none of them is parked.
+    fn maybe_grow(&self) {
+        let pool = &self.injector.pool;
+
+        if pool.keep_alive().is_none() || self.injector.idle.has_sleepers() {
+            return;
+        }
+
+        if self.injector.queue.total_len() <= pool.active() {
+            return;
+        }
+
+        if let Some(slot) = pool.claim() {
+            executor::spawn_worker(&self.handle, slot, None);
+        }
+    }
+
+    fn has_work(&self) -> bool {
+        self.has_shared_work() || self.locals[self.id].len() > 0
+    }
+
+    fn has_shared_work(&self) -> bool {
+        !self.injector.queue.is_empty()
+    }
+
+    fn poll(&self, task: Arc<dyn Runnable>) {
+        match self.injector.activity.get(self.id) {
+            Some(activity) => {
+                activity.enter(&task);
+                run(task);
+                activity.leave();
             }
+            None => run(task),
         }
     }
 
+    fn metrics(&self) -> &metrics::WorkerMetrics {
+        &self.injector.metrics.workers[self.id]
+    }
+
     fn try_steal(&self) -> Option<Arc<dyn Runnable>> {
         let len = self.locals.len();
 
@@ -139,13 +750,45 @@ impl Worker {
             let victim = (self.id + i + 1) % len;
 
             if let Some(task) = self.locals[victim].steal() {
+                metrics::incr(&self.metrics().steals);
                 return Some(task);
             }
         }
         None
     }
+
+    pub(crate) fn hand_off_local_queue(&self) {
+        let local = &self.locals[self.id];
+
+        self.injector.requeue(std::iter::from_fn(|| local.steal()));
+    }
 }
 
+/// Polls `task` once. A panic is reported with the task's ID and name even
+/// when nobody joins the task to see it.
+pub(crate) fn run(task: Arc<dyn Runnable>) {
+    if let Err(message) = task.poll() {
+        eprintln!("{}", message);
+    }
+}
+
+/// Index of the calling worker thread, if it belongs to `injector`'s runtime.
+pub(crate) fn current_worker(injector: &Arc<Injector>) -> Option<usize> {
+    CURRENT_WORKER.with(|cell| {
+        cell.borrow()
+            .as_ref()
+            .filter(|worker| Arc::ptr_eq(&worker.injector, injector))
+            .map(|worker| worker.id)
+    })
+}
+
+/// Most tasks a worker takes from the shared queue at once.
+const MAX_BATCH: usize = 16;
+
+/// Tasks a worker polls between two turns of the driver while it has work.
+const EVENT_INTERVAL: u32 = 61;
+
 thread_local! {
     pub static CURRENT_INJECTOR: RefCell<Option<Arc<Injector>>> = const { RefCell::new(None) };
+    pub(crate) static CURRENT_WORKER: RefCell<Option<Arc<Worker>>> = const { RefCell::new(None) };
 }
@@ -0,0 +1,60 @@
+use crate::core::task::{JoinHandle, Priority, Task, TaskConfig};
+use crate::runtime::workstealing::CURRENT_INJECTOR;
+
+use std::future::Future;
+
+/// Configures a task before spawning it.
+///
+/// ```no_run
+/// # let rt = cadentis::RuntimeBuilder::new().build();
+/// # rt.block_on(async {
+/// let handle = cadentis::task::Builder::new()
+///     .name("conn-handler")
+///     .spawn(async { 42 });
+///
+/// assert_eq!(handle.await, 42);
+/// # });
+/// ```
+#[derive(Debug, Default)]
+pub struct Builder {
+    config: TaskConfig,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names the task. The name shows up in panic messages.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.config.name = Some(name.into());
+        self
+    }
+
+    /// Sets the scheduling class of the task. Defaults to [`Priority::Normal`].
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.config.priority = priority;
+        self
+    }
+
+    /// Spawns the task on the current runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called outside of a runtime context.
+    #[track_caller]
+    pub fn spawn<F, T>(self, future: F) -> JoinHandle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + Sync + 'static,
+    {
+        let injector = CURRENT_INJECTOR.with(|cell| {
+            cell.borrow()
+                .as_ref()
+                .expect("Builder::spawn() called outside of a runtime context")
+                .clone()
+        });
+
+        Task::spawn_with(future, injector, self.config)
+    }
+}
@@ -0,0 +1,154 @@
+use crate::core::task::{Id, JoinError, JoinHandle, Task};
+
+use std::collections::{HashMap, VecDeque};
+use std::future::{Future, poll_fn};
+use std::sync::{Arc, Mutex};
+use std::task::{Poll, Wake, Waker};
+
+/// A set of tasks whose results are yielded in completion order.
+///
+/// Dropping the set aborts every task still in it.
+pub struct JoinSet<T: Send + Sync + 'static> {
+    tasks: HashMap<Id, JoinHandle<T>>,
+    ready: Arc<Ready>,
+}
+
+#[derive(Default)]
+struct Ready {
+    inner: Mutex<ReadyInner>,
+}
+
+#[derive(Default)]
+struct ReadyInner {
+    ids: VecDeque<Id>,
+    waker: Option<Waker>,
+}
+
+impl Ready {
+    fn push(&self, id: Id) {
+        let waker = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.ids.push_back(id);
+            inner.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Registered in a task's waiter list; reports the task as finished to its set.
+struct Notify {
+    id: Id,
+    ready: Arc<Ready>,
+}
+
+impl Wake for Notify {
+    fn wake(self: Arc<Self>) {
+        self.ready.push(self.id);
+    }
+}
+
+impl<T: Send + Sync + 'static> JoinSet<T> {
+    pub fn new() -> Self {
+        Self {
+            tasks: HashMap::new(),
+            ready: Arc::new(Ready::default()),
+        }
+    }
+
+    /// Spawns `future` on the current runtime and adds it to the set.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called outside of a runtime context.
+    #[track_caller]
+    pub fn spawn<F>(&mut self, future: F) -> Id
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let handle = Task::spawn(future);
+        let id = handle.id();
+
+        {
+            let mut waiters = handle.task.waiters.lock().unwrap();
+
+            if handle.task.is_finished() {
+                self.ready.push(id);
+            } else {
+                waiters.push(Waker::from(Arc::new(Notify {
+                    id,
+                    ready: self.ready.clone(),
+                })));
+            }
+        }
+
+        self.tasks.insert(id, handle);
+
+        id
+    }
+
+    /// Waits for the next task to finish and returns its output, or `None`
+    /// once the set is empty. A task that panicked, was aborted or was
+    /// cancelled at shutdown yields a [`JoinError`].
+    pub async fn join_next(&mut self) -> Option<Result<T, JoinError>> {
+        poll_fn(|cx| {
+            if self.tasks.is_empty() {
+                return Poll::Ready(None);
+            }
+
+            let mut inner = self.ready.inner.lock().unwrap();
+
+            while let Some(id) = inner.ids.pop_front() {
+                if let Some(handle) = self.tasks.remove(&id) {
+                    drop(inner);
+
+                    return Poll::Ready(Some(handle.try_take_result()));
+                }
+            }
+
+            inner.waker = Some(cx.waker().clone());
+
+            Poll::Pending
+        })
+        .await
+    }
+
+    /// Aborts the task with the given ID. It stays in the set, and `join_next`
+    /// yields a cancelled [`JoinError`] for it once it stops.
+    pub fn abort(&self, id: Id) {
+        if let Some(handle) = self.tasks.get(&id) {
+            handle.abort();
+        }
+    }
+
+    /// Aborts every task in the set and removes them from it.
+    pub fn abort_all(&mut self) {
+        for (_, handle) in self.tasks.drain() {
+            handle.abort();
+        }
+
+        self.ready.inner.lock().unwrap().ids.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
+
+impl<T: Send + Sync + 'static> Default for JoinSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send + Sync + 'static> Drop for JoinSet<T> {
+    fn drop(&mut self) {
+        self.abort_all();
+    }
+}
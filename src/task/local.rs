@@ -0,0 +1,246 @@
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::ptr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+/// Declares one or more task-local keys of type [`LocalKey`].
+///
+/// ```
+/// cadentis::task_local! {
+///     pub static REQUEST_ID: u64;
+/// }
+/// ```
+#[macro_export]
+macro_rules! task_local {
+    () => {};
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty; $($rest:tt)*) => {
+        $(#[$attr])*
+        $vis static $name: $crate::task::LocalKey<$t> = $crate::task::LocalKey::__new();
+
+        $crate::task_local!($($rest)*);
+    };
+
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty) => {
+        $crate::task_local!($(#[$attr])* $vis static $name: $t;);
+    };
+}
+
+#[derive(Clone)]
+struct Slot {
+    value: Arc<dyn Any + Send + Sync>,
+    inherit: bool,
+}
+
+/// Task-local values, stored in the task header next to its future.
+#[derive(Default)]
+pub(crate) struct TaskLocals {
+    slots: HashMap<usize, Slot>,
+}
+
+thread_local! {
+    static CURRENT_LOCALS: Cell<*mut TaskLocals> = const { Cell::new(ptr::null_mut()) };
+}
+
+impl TaskLocals {
+    /// Copies the inheritable values of the task running on this thread.
+    pub(crate) fn inherited() -> Self {
+        let current = CURRENT_LOCALS.with(Cell::get);
+
+        if current.is_null() {
+            return Self::default();
+        }
+
+        let slots = unsafe { &*current }
+            .slots
+            .iter()
+            .filter(|(_, slot)| slot.inherit)
+            .map(|(key, slot)| (*key, slot.clone()))
+            .collect();
+
+        Self { slots }
+    }
+
+    /// Makes `locals` the current task-local map until the guard is dropped.
+    pub(crate) fn enter(locals: *mut TaskLocals) -> LocalsGuard {
+        let prev = CURRENT_LOCALS.with(|current| current.replace(locals));
+
+        LocalsGuard { prev }
+    }
+}
+
+pub(crate) struct LocalsGuard {
+    prev: *mut TaskLocals,
+}
+
+impl Drop for LocalsGuard {
+    fn drop(&mut self) {
+        CURRENT_LOCALS.with(|current| current.set(self.prev));
+    }
+}
+
+/// A key for a task-local value, declared with [`task_local!`](crate::task_local).
+pub struct LocalKey<T: 'static> {
+    _id: u8,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Returned by [`LocalKey::try_with`] when the value is not set for the current task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccessError;
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("task-local value not set")
+    }
+}
+
+impl std::error::Error for AccessError {}
+
+impl<T: Send + Sync + 'static> LocalKey<T> {
+    #[doc(hidden)]
+    pub const fn __new() -> Self {
+        Self {
+            _id: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    fn key(&'static self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// Sets the value for the duration of `future`. Tasks spawned from it do not see it.
+    pub fn scope<F: Future>(&'static self, value: T, future: F) -> TaskLocalFuture<T, F> {
+        self.scope_with(value, future, false)
+    }
+
+    /// Sets the value for the duration of `future` and copies it into every task
+    /// spawned from within it.
+    pub fn scope_inherited<F: Future>(&'static self, value: T, future: F) -> TaskLocalFuture<T, F> {
+        self.scope_with(value, future, true)
+    }
+
+    fn scope_with<F: Future>(
+        &'static self,
+        value: T,
+        future: F,
+        inherit: bool,
+    ) -> TaskLocalFuture<T, F> {
+        TaskLocalFuture {
+            key: self,
+            slot: Some(Slot {
+                value: Arc::new(value),
+                inherit,
+            }),
+            future,
+        }
+    }
+
+    /// Calls `function` with a reference to the current value.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the value is not set for the current task.
+    pub fn with<F, R>(&'static self, function: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.try_with(function)
+            .expect("task-local value not set for the current task")
+    }
+
+    pub fn try_with<F, R>(&'static self, function: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        let current = CURRENT_LOCALS.with(Cell::get);
+
+        if current.is_null() {
+            return Err(AccessError);
+        }
+
+        let value = unsafe { &*current }
+            .slots
+            .get(&self.key())
+            .map(|slot| slot.value.clone())
+            .ok_or(AccessError)?;
+
+        let value = value.downcast_ref::<T>().ok_or(AccessError)?;
+
+        Ok(function(value))
+    }
+
+    pub fn get(&'static self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+}
+
+/// Future returned by [`LocalKey::scope`] and [`LocalKey::scope_inherited`].
+pub struct TaskLocalFuture<T: 'static, F> {
+    key: &'static LocalKey<T>,
+    slot: Option<Slot>,
+    future: F,
+}
+
+impl<T: Send + Sync + 'static, F: Future> Future for TaskLocalFuture<T, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = unsafe { self.get_unchecked_mut() };
+        let key = this.key.key();
+
+        let mut fallback = TaskLocals::default();
+        let mut current = CURRENT_LOCALS.with(Cell::get);
+        let _guard = current
+            .is_null()
+            .then(|| TaskLocals::enter(&mut fallback as *mut TaskLocals));
+
+        if current.is_null() {
+            current = &mut fallback as *mut TaskLocals;
+        }
+
+        struct Restore<'a> {
+            locals: *mut TaskLocals,
+            key: usize,
+            slot: &'a mut Option<Slot>,
+            prev: Option<Slot>,
+        }
+
+        impl Drop for Restore<'_> {
+            fn drop(&mut self) {
+                let locals = unsafe { &mut *self.locals };
+
+                *self.slot = locals.slots.remove(&self.key);
+
+                if let Some(prev) = self.prev.take() {
+                    locals.slots.insert(self.key, prev);
+                }
+            }
+        }
+
+        let value = this
+            .slot
+            .take()
+            .expect("TaskLocalFuture polled after completion");
+        let prev = unsafe { &mut *current }.slots.insert(key, value);
+
+        let _restore = Restore {
+            locals: current,
+            key,
+            slot: &mut this.slot,
+            prev,
+        };
+
+        unsafe { Pin::new_unchecked(&mut this.future) }.poll(cx)
+    }
+}
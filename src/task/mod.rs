@@ -0,0 +1,15 @@
+mod builder;
+mod join_set;
+pub(crate) mod local;
+mod scope;
+mod spawn;
+
+pub use crate::core::task::{Id, JoinError, JoinHandle, Priority, Task, id, try_id};
+pub use crate::runtime::blocking::{block_in_place, spawn_blocking};
+pub use crate::runtime::budget::{Unconstrained, consume_budget, unconstrained};
+pub use crate::runtime::yield_now::yield_now;
+pub use builder::Builder;
+pub use join_set::JoinSet;
+pub use local::{AccessError, LocalKey, TaskLocalFuture};
+pub use scope::{Scope, ScopeFuture, scope};
+pub use spawn::spawn_on;
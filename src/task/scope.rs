@@ -0,0 +1,161 @@
+use std::future::Future;
+use std::marker::PhantomData;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+type Child<'a, E> = Pin<Box<dyn Future<Output = Result<(), E>> + Send + 'a>>;
+
+/// Runs `body` with a [`Scope`] whose children may borrow from the caller.
+///
+/// Children are polled by the returned future itself rather than by the
+/// workers, so they never outlive it: when it completes every child has
+/// finished, and when it is dropped (or leaked) no child runs again.
+///
+/// The first error returned by the body or a child drops all remaining
+/// children and is returned from the scope.
+///
+/// ```no_run
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// # let rt = cadentis::RuntimeBuilder::new().build();
+/// let words = ["a", "bc", "def"];
+/// let total = AtomicUsize::new(0);
+/// let (words, total) = (&words, &total);
+///
+/// rt.block_on(cadentis::task::scope(|s| async move {
+///     for word in words {
+///         s.spawn(async move {
+///             total.fetch_add(word.len(), Ordering::Relaxed);
+///             Ok::<_, ()>(())
+///         });
+///     }
+///     Ok(())
+/// }))
+/// .unwrap();
+///
+/// assert_eq!(total.load(Ordering::Relaxed), 6);
+/// ```
+pub fn scope<'a, F, Fut, R, E>(body: F) -> ScopeFuture<'a, Fut, R, E>
+where
+    F: FnOnce(Scope<'a, E>) -> Fut,
+    Fut: Future<Output = Result<R, E>> + Send + 'a,
+    E: Send + 'a,
+{
+    let scope = Scope {
+        spawned: Arc::new(Mutex::new(Vec::new())),
+        _marker: PhantomData,
+    };
+
+    ScopeFuture {
+        body: Some(Box::pin(body(scope.clone()))),
+        output: None,
+        children: Vec::new(),
+        scope,
+    }
+}
+
+/// Handle used to spawn children into a [`scope`].
+pub struct Scope<'a, E> {
+    spawned: Arc<Mutex<Vec<Child<'a, E>>>>,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<E> Clone for Scope<'_, E> {
+    fn clone(&self) -> Self {
+        Self {
+            spawned: self.spawned.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, E: Send + 'a> Scope<'a, E> {
+    pub fn spawn<F>(&self, future: F)
+    where
+        F: Future<Output = Result<(), E>> + Send + 'a,
+    {
+        self.spawned.lock().unwrap().push(Box::pin(future));
+    }
+}
+
+/// Future returned by [`scope`].
+pub struct ScopeFuture<'a, Fut, R, E> {
+    body: Option<Pin<Box<Fut>>>,
+    output: Option<R>,
+    children: Vec<Child<'a, E>>,
+    scope: Scope<'a, E>,
+}
+
+// The body and children are boxed and the output is never pinned, so nothing
+// relies on the future staying in place.
+impl<Fut, R, E> Unpin for ScopeFuture<'_, Fut, R, E> {}
+
+impl<Fut, R, E> ScopeFuture<'_, Fut, R, E> {
+    fn cancel(&mut self) {
+        self.body = None;
+        self.children.clear();
+        mem::take(&mut *self.scope.spawned.lock().unwrap());
+    }
+}
+
+impl<'a, Fut, R, E> Future for ScopeFuture<'a, Fut, R, E>
+where
+    Fut: Future<Output = Result<R, E>>,
+{
+    type Output = Result<R, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if let Some(body) = this.body.as_mut()
+            && let Poll::Ready(result) = body.as_mut().poll(cx)
+        {
+            this.body = None;
+
+            match result {
+                Ok(value) => this.output = Some(value),
+                Err(err) => {
+                    this.cancel();
+                    return Poll::Ready(Err(err));
+                }
+            }
+        }
+
+        loop {
+            let spawned = mem::take(&mut *this.scope.spawned.lock().unwrap());
+
+            if spawned.is_empty() && this.children.is_empty() {
+                break;
+            }
+
+            this.children.extend(spawned);
+
+            let mut i = 0;
+            while i < this.children.len() {
+                match this.children[i].as_mut().poll(cx) {
+                    Poll::Ready(Ok(())) => drop(this.children.swap_remove(i)),
+                    Poll::Ready(Err(err)) => {
+                        this.cancel();
+                        return Poll::Ready(Err(err));
+                    }
+                    Poll::Pending => i += 1,
+                }
+            }
+
+            if this.scope.spawned.lock().unwrap().is_empty() {
+                break;
+            }
+        }
+
+        if this.body.is_none() && this.children.is_empty() {
+            return Poll::Ready(Ok(this
+                .output
+                .take()
+                .expect("ScopeFuture polled after completion")));
+        }
+
+        Poll::Pending
+    }
+}
@@ -0,0 +1,39 @@
+use crate::core::task::{JoinHandle, Task, TaskConfig};
+use crate::runtime::workstealing::CURRENT_INJECTOR;
+
+use std::future::Future;
+
+/// Spawns a task that is only ever polled by worker `worker`.
+///
+/// The task is queued on that worker's local queue, is never stolen, and goes
+/// back to the same worker every time it is woken.
+///
+/// # Panics
+///
+/// Panics when called outside of a runtime context, or when `worker` is not a
+/// valid worker index of the current runtime.
+#[track_caller]
+pub fn spawn_on<F, T>(worker: usize, future: F) -> JoinHandle<T>
+where
+    F: Future<Output = T> + Send + 'static,
+    T: Send + Sync + 'static,
+{
+    let injector = CURRENT_INJECTOR
+        .with(|cell| cell.borrow().clone())
+        .expect("task::spawn_on() called outside of a runtime context");
+
+    let workers = injector.locals.len();
+    assert!(
+        worker < workers,
+        "task::spawn_on() worker index {} out of range ({} workers)",
+        worker,
+        workers
+    );
+
+    let config = TaskConfig {
+        home: Some(worker),
+        ..TaskConfig::default()
+    };
+
+    Task::spawn_with(future, injector, config)
+}
@@ -0,0 +1,156 @@
+use crate::runtime::context::{CURRENT_CLOCK, CURRENT_REACTOR, CURRENT_TIME};
+use crate::runtime::yield_now::yield_now;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Source of time for a runtime.
+///
+/// A running clock follows the wall clock from the point it was last resumed.
+/// A paused clock stands still and only moves through [`Clock::advance`] or when
+/// the runtime auto-advances it to the next timer because nothing else can run.
+pub(crate) struct Clock {
+    state: Mutex<State>,
+}
+
+struct State {
+    /// Virtual time at `real_anchor`, or the frozen time while paused.
+    virtual_anchor: Instant,
+    real_anchor: Instant,
+    paused: bool,
+}
+
+impl Clock {
+    pub(crate) fn new(paused: bool) -> Self {
+        let now = Instant::now();
+
+        Self {
+            state: Mutex::new(State {
+                virtual_anchor: now,
+                real_anchor: now,
+                paused,
+            }),
+        }
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.state.lock().unwrap().paused
+    }
+
+    pub(crate) fn now(&self) -> Instant {
+        self.state.lock().unwrap().now()
+    }
+
+    pub(crate) fn pause(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        state.virtual_anchor = state.now();
+        state.paused = true;
+    }
+
+    pub(crate) fn resume(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        state.virtual_anchor = state.now();
+        state.real_anchor = Instant::now();
+        state.paused = false;
+    }
+
+    /// Moves a paused clock forward by `duration`.
+    pub(crate) fn advance(&self, duration: Duration) {
+        let mut state = self.state.lock().unwrap();
+
+        if !state.paused {
+            // Release the lock first so the panic does not poison the clock.
+            drop(state);
+            panic!("time::advance() requires a paused clock");
+        }
+
+        state.virtual_anchor += duration;
+    }
+
+    /// Moves a paused clock forward to `deadline`. Never moves it backwards.
+    pub(crate) fn advance_to(&self, deadline: Instant) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.paused && deadline > state.virtual_anchor {
+            state.virtual_anchor = deadline;
+        }
+    }
+}
+
+impl State {
+    fn now(&self) -> Instant {
+        if self.paused {
+            self.virtual_anchor
+        } else {
+            self.virtual_anchor + self.real_anchor.elapsed()
+        }
+    }
+}
+
+/// Clock of the runtime driving this thread.
+pub(crate) fn current() -> Option<Arc<Clock>> {
+    CURRENT_CLOCK.with(|cell| cell.borrow().clone())
+}
+
+/// Current time of the runtime driving this thread, or the real time outside of one.
+pub(crate) fn now() -> Instant {
+    CURRENT_CLOCK.with(|cell| match cell.borrow().as_ref() {
+        Some(clock) => clock.now(),
+        None => Instant::now(),
+    })
+}
+
+fn expect_current(caller: &str) -> Arc<Clock> {
+    current().unwrap_or_else(|| panic!("{} called outside of a runtime context", caller))
+}
+
+/// Freezes the runtime clock.
+///
+/// While paused, timers only fire when the clock is moved with [`advance`], or
+/// automatically once no task can make progress, in which case the clock jumps
+/// straight to the next pending timer.
+///
+/// # Panics
+///
+/// Panics when called outside of a runtime context.
+pub fn pause() {
+    expect_current("time::pause()").pause();
+}
+
+/// Lets a paused clock follow the wall clock again, from the time it was paused at.
+///
+/// # Panics
+///
+/// Panics when called outside of a runtime context.
+pub fn resume() {
+    expect_current("time::resume()").resume();
+
+    // The parked driver waits without a timeout while the clock is paused.
+    if let Some(time) = CURRENT_TIME.with(|cell| cell.borrow().clone()) {
+        time.unpark();
+    }
+}
+
+/// Moves a paused clock forward by `duration`, fires every timer that became
+/// due and yields so that the woken tasks get to run.
+///
+/// # Panics
+///
+/// Panics when called outside of a runtime context or when the clock is not paused.
+pub async fn advance(duration: Duration) {
+    expect_current("time::advance()").advance(duration);
+
+    if let Some(reactor) = CURRENT_REACTOR.with(|cell| cell.borrow().clone()) {
+        let mut reactor = reactor.lock().unwrap();
+        reactor.poll_events();
+        reactor.wake_ready();
+    }
+
+    if let Some(time) = CURRENT_TIME.with(|cell| cell.borrow().clone()) {
+        time.process();
+    }
+
+    yield_now().await;
+}
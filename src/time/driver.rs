@@ -0,0 +1,167 @@
+use crate::reactor::core::ReactorUnpark;
+use crate::time::clock::Clock;
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::Waker;
+use std::time::{Duration, Instant};
+
+/// Used in place of deadlines too far away to be represented.
+const FAR_FUTURE: Duration = Duration::from_secs(86_400 * 365 * 30);
+
+/// Pending timers of a runtime, ordered by deadline.
+///
+/// Independent of the I/O poller: the worker parked in the reactor only bounds
+/// its wait by the next deadline, and timers fire whenever the runtime turns
+/// the reactor, whether or not I/O is enabled.
+pub(crate) struct TimeDriver {
+    clock: Arc<Clock>,
+    unpark: ReactorUnpark,
+    state: Mutex<State>,
+}
+
+struct State {
+    timers: BTreeMap<TimerKey, Timer>,
+    next_id: u64,
+}
+
+struct Timer {
+    waker: Waker,
+    expired: Arc<AtomicBool>,
+}
+
+/// Identifies a registered timer, to cancel it with [`TimeDriver::cancel`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct TimerKey {
+    deadline: Instant,
+    id: u64,
+}
+
+impl TimeDriver {
+    pub(crate) fn new(clock: Arc<Clock>, unpark: ReactorUnpark) -> Self {
+        Self {
+            clock,
+            unpark,
+            state: Mutex::new(State {
+                timers: BTreeMap::new(),
+                next_id: 0,
+            }),
+        }
+    }
+
+    /// Deadline `duration` from now on the runtime clock.
+    pub(crate) fn deadline_after(&self, duration: Duration) -> Instant {
+        let now = self.clock.now();
+
+        now.checked_add(duration).unwrap_or(now + FAR_FUTURE)
+    }
+
+    /// Wakes `waker` and sets `expired` once the clock reaches `deadline`.
+    pub(crate) fn register(
+        &self,
+        deadline: Instant,
+        waker: Waker,
+        expired: Arc<AtomicBool>,
+    ) -> TimerKey {
+        let mut state = self.state.lock().unwrap();
+
+        let key = TimerKey {
+            deadline,
+            id: state.next_id,
+        };
+        state.next_id += 1;
+
+        let earliest = state.timers.keys().next().is_none_or(|first| key < *first);
+        state.timers.insert(key, Timer { waker, expired });
+        drop(state);
+
+        // The parked driver waits until the previous earliest deadline.
+        if earliest && !self.clock.is_paused() {
+            self.unpark.unpark();
+        }
+
+        key
+    }
+
+    /// Forgets a timer that has not fired yet.
+    pub(crate) fn cancel(&self, key: TimerKey) {
+        self.state.lock().unwrap().timers.remove(&key);
+    }
+
+    /// Fires every timer that is due and wakes its task.
+    pub(crate) fn process(&self) {
+        let now = self.clock.now();
+        let mut wakers = Vec::new();
+
+        {
+            let mut state = self.state.lock().unwrap();
+
+            while let Some(entry) = state.timers.first_entry() {
+                if entry.key().deadline > now {
+                    break;
+                }
+
+                let timer = entry.remove();
+                timer.expired.store(true, Ordering::Release);
+                wakers.push(timer.waker);
+            }
+        }
+
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// Moves a paused clock to the earliest pending timer and fires it.
+    /// Returns `false` when the clock is running or no timer is pending.
+    pub(crate) fn advance_to_next(&self) -> bool {
+        if !self.clock.is_paused() {
+            return false;
+        }
+
+        let Some(next) = self.next_deadline() else {
+            return false;
+        };
+
+        self.clock.advance_to(next);
+        self.process();
+
+        true
+    }
+
+    /// How long the driver may block before the next timer is due. `None` when
+    /// no timer is pending or the clock is paused, as a paused clock only moves
+    /// through `time::advance` or auto-advance.
+    pub(crate) fn park_timeout(&self) -> Option<Duration> {
+        if self.clock.is_paused() {
+            return None;
+        }
+
+        let next = self.next_deadline()?;
+
+        Some(next.saturating_duration_since(self.clock.now()))
+    }
+
+    /// Interrupts the parked driver so that it recomputes its timeout, e.g.
+    /// after the clock was resumed.
+    pub(crate) fn unpark(&self) {
+        self.unpark.unpark();
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.state.lock().unwrap().timers.len()
+    }
+
+    pub(crate) fn shutdown(&self) {
+        let timers = std::mem::take(&mut self.state.lock().unwrap().timers);
+
+        drop(timers);
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        let state = self.state.lock().unwrap();
+
+        state.timers.keys().next().map(|key| key.deadline)
+    }
+}
@@ -1,7 +1,12 @@
+pub(crate) mod clock;
+pub(crate) mod driver;
+mod instant;
 mod sleep;
 mod timeout;
 mod wrapper;
 
-pub use sleep::sleep;
+pub use clock::{advance, pause, resume};
+pub use instant::Instant;
+pub use sleep::{sleep, try_sleep};
 pub use timeout::timeout;
 pub use wrapper::Time;
@@ -1,64 +1,82 @@
-use crate::reactor::core::ReactorHandle;
-use crate::runtime::context::current_reactor_io;
+use crate::ContextError;
+use crate::runtime::budget::poll_proceed;
+use crate::runtime::context::{current_time_driver, try_current_time_driver};
+use crate::time::driver::{TimeDriver, TimerKey};
 
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, ready};
 use std::time::Duration;
 
 pub struct Sleep {
     duration: Duration,
-    reactor: ReactorHandle,
-    registered: bool,
+    driver: Arc<TimeDriver>,
+    timer: Option<TimerKey>,
     expired: Arc<AtomicBool>,
 }
 
 impl Sleep {
     pub(crate) fn new(duration: Duration) -> Self {
-        Self::new_with_reactor(duration, current_reactor_io())
+        Self::new_with_driver(duration, current_time_driver())
     }
 
-    pub(crate) fn new_with_reactor(duration: Duration, reactor: ReactorHandle) -> Self {
+    pub(crate) fn new_with_driver(duration: Duration, driver: Arc<TimeDriver>) -> Self {
         Self {
             duration,
-            reactor,
-            registered: false,
+            driver,
+            timer: None,
             expired: Arc::new(AtomicBool::new(false)),
         }
     }
 }
 
+/// Waits until `duration` has elapsed on the runtime clock.
+///
+/// # Panics
+///
+/// Panics outside of a runtime, or when the runtime was built without
+/// `RuntimeBuilder::enable_time()`.
 pub fn sleep(duration: Duration) -> Sleep {
     Sleep::new(duration)
 }
 
+/// Like [`sleep`], but fails instead of panicking when the runtime cannot
+/// drive timers.
+pub fn try_sleep(duration: Duration) -> Result<Sleep, ContextError> {
+    Ok(Sleep::new_with_driver(duration, try_current_time_driver()?))
+}
+
 impl Future for Sleep {
     type Output = ();
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        if self.duration.is_zero() {
-            return Poll::Ready(());
-        }
+        let coop = ready!(poll_proceed(cx));
 
-        if self.expired.load(Ordering::Acquire) {
+        if self.duration.is_zero() || self.expired.load(Ordering::Acquire) {
+            coop.made_progress();
             return Poll::Ready(());
         }
 
-        if !self.registered {
+        if self.timer.is_none() {
+            let deadline = self.driver.deadline_after(self.duration);
             let waker = cx.waker().clone();
             let expired = self.expired.clone();
 
-            self.reactor.lock().unwrap().register_timer_with_callback(
-                self.duration,
-                waker,
-                expired,
-            );
-
-            self.registered = true;
+            self.timer = Some(self.driver.register(deadline, waker, expired));
         }
 
         Poll::Pending
     }
 }
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(timer) = self.timer
+            && !self.expired.load(Ordering::Acquire)
+        {
+            self.driver.cancel(timer);
+        }
+    }
+}
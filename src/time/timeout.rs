@@ -1,11 +1,19 @@
-use crate::reactor::core::ReactorHandle;
-use crate::runtime::context::current_reactor_io;
+use crate::runtime::context::current_time_driver;
+use crate::time::Instant;
+use crate::time::driver::{TimeDriver, TimerKey};
 
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
+/// Runs `future` until it completes or `duration` elapses on the runtime clock.
+///
+/// # Panics
+///
+/// Panics outside of a runtime, or when the runtime was built without
+/// `RuntimeBuilder::enable_time()`.
 pub fn timeout<F>(duration: Duration, future: F) -> Timeout<F>
 where
     F: Future,
@@ -16,9 +24,8 @@ where
 pub struct Timeout<F> {
     future: F,
     deadline: Instant,
-    duration: Duration,
-    reactor: ReactorHandle,
-    registered: bool,
+    driver: Arc<TimeDriver>,
+    timer: Option<TimerKey>,
 }
 
 impl<F> Timeout<F> {
@@ -26,9 +33,8 @@ impl<F> Timeout<F> {
         Timeout {
             future,
             deadline: Instant::now() + duration,
-            duration,
-            reactor: current_reactor_io(),
-            registered: false,
+            driver: current_time_driver(),
+            timer: None,
         }
     }
 }
@@ -46,20 +52,26 @@ impl<F: Future> Future for Timeout<F> {
             return Poll::Ready(Ok(v));
         }
 
-        if !self.registered {
-            let waker = cx.waker().clone();
-
-            self.reactor
-                .lock()
-                .unwrap()
-                .register_timer(self.duration, waker);
+        if self.timer.is_none() {
+            let deadline = self.deadline.into_std();
+            let timer = self
+                .driver
+                .register(deadline, cx.waker().clone(), Arc::default());
 
             unsafe {
                 let this = self.get_unchecked_mut();
-                this.registered = true;
+                this.timer = Some(timer);
             }
         }
 
         Poll::Pending
     }
 }
+
+impl<F> Drop for Timeout<F> {
+    fn drop(&mut self) {
+        if let Some(timer) = self.timer {
+            self.driver.cancel(timer);
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use crate::JoinHandle;
+use crate::time::Instant;
 
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 pub struct Time<T: Send + Sync + 'static> {
     start: Instant,
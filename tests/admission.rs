@@ -0,0 +1,153 @@
+use cadentis::time::{sleep, timeout};
+use cadentis::{Handle, RuntimeBuilder, SpawnError, Task};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_try_spawn_fails_at_task_limit() {
+    let rt = RuntimeBuilder::new().enable_io().max_tasks(2).build();
+
+    rt.block_on(async {
+        let handle = Handle::current();
+        let first = handle.try_spawn(sleep(Duration::from_millis(50))).unwrap();
+        let second = handle.try_spawn(sleep(Duration::from_millis(50))).unwrap();
+
+        let refused = handle.try_spawn(async {});
+        assert_eq!(refused.err(), Some(SpawnError));
+
+        first.await;
+        second.await;
+
+        let value = handle.try_spawn(async { 3 }).unwrap().await;
+        assert_eq!(value, 3);
+    });
+}
+
+#[test]
+fn test_try_spawn_without_limit() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    rt.block_on(async {
+        let handle = Handle::current();
+        let tasks: Vec<_> = (0..100)
+            .map(|_| handle.try_spawn(sleep(Duration::from_millis(10))).unwrap())
+            .collect();
+
+        for task in tasks {
+            task.await;
+        }
+    });
+}
+
+#[test]
+fn test_spawn_counts_towards_limit() {
+    let rt = RuntimeBuilder::new().enable_io().max_tasks(1).build();
+
+    rt.block_on(async {
+        let first = Task::spawn(sleep(Duration::from_millis(50)));
+        let second = Task::spawn(sleep(Duration::from_millis(50)));
+
+        assert!(Handle::current().try_spawn(async {}).is_err());
+
+        first.await;
+        second.await;
+    });
+}
+
+#[test]
+fn test_spawn_wait_waits_for_capacity() {
+    let rt = RuntimeBuilder::new().enable_io().max_tasks(1).build();
+
+    rt.block_on(async {
+        let handle = Handle::current();
+        let start = Instant::now();
+        let busy = Task::spawn(sleep(Duration::from_millis(50)));
+
+        let task = handle.spawn_wait(async { 7 }).await;
+
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(task.await, 7);
+        busy.await;
+    });
+}
+
+#[test]
+fn test_spawn_wait_serves_every_waiter() {
+    let rt = RuntimeBuilder::new().enable_io().max_tasks(2).build();
+
+    rt.block_on(async {
+        let handle = Handle::current();
+        let mut tasks = Vec::new();
+
+        for i in 0..20 {
+            let task = handle
+                .spawn_wait(async move {
+                    sleep(Duration::from_millis(1)).await;
+                    i
+                })
+                .await;
+            tasks.push(task);
+        }
+
+        let mut total = 0;
+        for task in tasks {
+            total += task.await;
+        }
+
+        assert_eq!(total, (0..20).sum::<i32>());
+    });
+}
+
+#[test]
+fn test_dropped_spawn_wait_releases_its_turn() {
+    let rt = RuntimeBuilder::new().enable_io().max_tasks(1).build();
+
+    rt.block_on(async {
+        let handle = Handle::current();
+        let busy = Task::spawn(sleep(Duration::from_millis(50)));
+
+        // Gives up while queued behind the busy task.
+        let gave_up = timeout(Duration::from_millis(10), handle.spawn_wait(async {})).await;
+        assert!(gave_up.is_err());
+
+        let task = timeout(Duration::from_secs(5), handle.spawn_wait(async { 1 }))
+            .await
+            .expect("the second waiter was never woken");
+
+        assert_eq!(task.await, 1);
+        busy.await;
+    });
+}
+
+#[test]
+fn test_spawn_wait_resolves_on_shutdown() {
+    let rt = RuntimeBuilder::new().max_tasks(1).build();
+    let handle = rt.handle().clone();
+    let _busy = handle.spawn(std::future::pending::<()>());
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let waiter = std::thread::spawn(move || {
+        let other = RuntimeBuilder::new().worker_threads(1).build();
+
+        other.block_on(async {
+            let wait = handle.spawn_wait(async { 1 });
+            tx.send(()).unwrap();
+
+            wait.await.is_finished()
+        })
+    });
+
+    rx.recv().unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+    rt.shutdown_timeout(Duration::from_secs(1));
+
+    assert!(
+        waiter.join().unwrap(),
+        "The task spawned on a closed runtime should be cancelled"
+    );
+}
+
+#[test]
+#[should_panic(expected = "max_tasks() must be at least 1")]
+fn test_max_tasks_rejects_zero() {
+    let _ = RuntimeBuilder::new().max_tasks(0);
+}
@@ -0,0 +1,115 @@
+use cadentis::task::{consume_budget, unconstrained};
+use cadentis::time::sleep;
+use cadentis::{RuntimeBuilder, Task};
+use std::future::{Future, poll_fn};
+use std::pin::{Pin, pin};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+struct CountPolls<F> {
+    future: Pin<Box<F>>,
+    polls: Arc<AtomicUsize>,
+}
+
+impl<F: Future> Future for CountPolls<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.polls.fetch_add(1, Ordering::SeqCst);
+        self.future.as_mut().poll(cx)
+    }
+}
+
+fn count_polls<F: Future>(future: F, polls: Arc<AtomicUsize>) -> CountPolls<F> {
+    CountPolls {
+        future: Box::pin(future),
+        polls,
+    }
+}
+
+async fn spin(iterations: usize) {
+    for _ in 0..iterations {
+        consume_budget().await;
+    }
+}
+
+#[test]
+fn test_exhausted_budget_forces_yield() {
+    let rt = RuntimeBuilder::new().build();
+    let polls = Arc::new(AtomicUsize::new(0));
+
+    rt.block_on(count_polls(spin(1000), polls.clone()));
+
+    assert!(
+        polls.load(Ordering::SeqCst) > 1,
+        "A long ready loop should be forced to yield"
+    );
+}
+
+#[test]
+fn test_budget_forces_yield_in_spawned_task() {
+    let rt = RuntimeBuilder::new().build();
+    let polls = Arc::new(AtomicUsize::new(0));
+    let polls_clone = polls.clone();
+
+    rt.block_on(async move { Task::spawn(count_polls(spin(1000), polls_clone)).await });
+
+    assert!(polls.load(Ordering::SeqCst) > 1);
+}
+
+#[test]
+fn test_unconstrained_never_yields() {
+    let rt = RuntimeBuilder::new().build();
+    let polls = Arc::new(AtomicUsize::new(0));
+
+    rt.block_on(count_polls(unconstrained(spin(1000)), polls.clone()));
+
+    assert_eq!(polls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_ready_sleep_loop_lets_timers_fire() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+    let fired = Arc::new(AtomicBool::new(false));
+    let fired_clone = fired.clone();
+
+    let iterations = rt.block_on(async move {
+        Task::spawn(async move {
+            sleep(Duration::from_millis(20)).await;
+            fired_clone.store(true, Ordering::SeqCst);
+        });
+
+        let mut iterations = 0usize;
+        while !fired.load(Ordering::SeqCst) {
+            sleep(Duration::ZERO).await;
+            iterations += 1;
+        }
+
+        iterations
+    });
+
+    assert!(iterations > 0);
+}
+
+#[test]
+fn test_pending_resource_does_not_consume_budget() {
+    let rt = RuntimeBuilder::new().enable_time().build();
+
+    let proceeded = rt.block_on(async {
+        let mut sleep = pin!(sleep(Duration::from_secs(60)));
+
+        poll_fn(|cx| {
+            for _ in 0..500 {
+                assert!(sleep.as_mut().poll(cx).is_pending());
+            }
+
+            let mut budget = pin!(consume_budget());
+            Poll::Ready(budget.as_mut().poll(cx).is_ready())
+        })
+        .await
+    });
+
+    assert!(proceeded, "Polls of an unready timer should be refunded");
+}
@@ -0,0 +1,88 @@
+use cadentis::fs::File;
+use cadentis::net::tcp_listener::TcpListener;
+use cadentis::time::try_sleep;
+use cadentis::{ContextError, Error, Handle, RuntimeBuilder, Task};
+use std::io;
+use std::time::Duration;
+
+#[test]
+fn test_try_bind_without_io() {
+    let rt = RuntimeBuilder::new().build();
+
+    let result = rt.block_on(TcpListener::try_bind("127.0.0.1:0"));
+
+    let error = result.err().expect("bind should fail without I/O");
+    assert!(matches!(error, Error::Context(ContextError::IoDisabled)));
+    assert!(error.to_string().contains("RuntimeBuilder::enable_io()"));
+}
+
+#[test]
+fn test_try_bind_with_io() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    let result = rt.block_on(TcpListener::try_bind("127.0.0.1:0"));
+
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_try_open_without_fs() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    let result = rt.block_on(File::try_open("Cargo.toml"));
+
+    let error = result.err().expect("open should fail without fs");
+    assert!(matches!(error, Error::Context(ContextError::FsDisabled)));
+    assert!(error.to_string().contains("RuntimeBuilder::enable_fs()"));
+}
+
+#[test]
+fn test_try_open_reports_io_errors() {
+    let rt = RuntimeBuilder::new().enable_fs().build();
+
+    let result = rt.block_on(File::try_open("does/not/exist"));
+
+    match result {
+        Err(Error::Io(error)) => assert_eq!(error.kind(), io::ErrorKind::NotFound),
+        other => panic!("expected an I/O error, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn test_try_sleep() {
+    let rt = RuntimeBuilder::new().build();
+    let result = rt.block_on(async { try_sleep(Duration::from_millis(1)).map(|_| ()) });
+    assert_eq!(result, Err(ContextError::TimeDisabled));
+
+    let rt = RuntimeBuilder::new().enable_time().build();
+    rt.block_on(async {
+        try_sleep(Duration::from_millis(1)).unwrap().await;
+    });
+}
+
+#[test]
+fn test_try_sleep_outside_runtime() {
+    let error = try_sleep(Duration::from_millis(1)).err();
+
+    assert_eq!(error, Some(ContextError::NoRuntime));
+}
+
+#[test]
+fn test_task_try_spawn() {
+    let error = Task::try_spawn(async { 1 }).err().unwrap();
+    assert!(matches!(error, Error::Context(ContextError::NoRuntime)));
+    assert!(error.to_string().contains("no runtime context"));
+
+    let rt = RuntimeBuilder::new().max_tasks(1).build();
+    let _guard = rt.handle().enter();
+
+    let first = Task::try_spawn(async {
+        std::thread::sleep(Duration::from_millis(50));
+        1
+    })
+    .unwrap();
+    let refused = Task::try_spawn(async { 2 }).err().unwrap();
+    assert!(matches!(refused, Error::Spawn(_)));
+
+    assert_eq!(Handle::current().block_on(first), 1);
+}
@@ -0,0 +1,81 @@
+use cadentis::task::spawn_on;
+use cadentis::{Handle, RuntimeBuilder};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
+
+fn active_workers(rt: &cadentis::Runtime) -> usize {
+    rt.handle().metrics().num_active_workers()
+}
+
+fn wait_for(mut condition: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    false
+}
+
+#[test]
+fn test_elastic_pool_starts_with_min_workers() {
+    let rt = RuntimeBuilder::new().min_workers(1).max_workers(4).build();
+    let metrics = rt.handle().metrics();
+
+    assert_eq!(metrics.num_workers(), 4);
+    assert_eq!(metrics.num_active_workers(), 1);
+}
+
+#[test]
+fn test_fixed_pool_runs_every_worker() {
+    let rt = RuntimeBuilder::new().worker_threads(3).build();
+
+    assert_eq!(active_workers(&rt), 3);
+}
+
+#[test]
+fn test_elastic_pool_grows_under_load_and_shrinks_when_idle() {
+    let rt = RuntimeBuilder::new()
+        .min_workers(1)
+        .max_workers(4)
+        .worker_keep_alive(Duration::from_millis(100))
+        .build();
+
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (tx, rx) = mpsc::channel();
+
+    for _ in 0..32 {
+        let peak = peak.clone();
+        let tx = tx.clone();
+
+        rt.spawn(async move {
+            std::thread::sleep(Duration::from_millis(10));
+
+            let active = Handle::current().metrics().num_active_workers();
+            peak.fetch_max(active, Ordering::Relaxed);
+
+            tx.send(()).unwrap();
+        });
+    }
+
+    for _ in 0..32 {
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    assert!(peak.load(Ordering::Relaxed) > 1);
+    assert!(wait_for(|| active_workers(&rt) == 1));
+}
+
+#[test]
+fn test_spawn_on_retired_worker_still_runs() {
+    let rt = RuntimeBuilder::new().min_workers(1).max_workers(4).build();
+
+    let value = rt.block_on(async { spawn_on(3, async { 7 }).await });
+
+    assert_eq!(value, 7);
+}
@@ -12,6 +12,7 @@ fn test_global_spawn_basic() {
             *completed_clone.lock().unwrap() = true;
         });
     });
+    rt.run_until_idle();
 
     assert!(
         *completed.lock().unwrap(),
@@ -41,6 +42,7 @@ fn test_global_spawn_multiple() {
             *c3.lock().unwrap() += 100;
         });
     });
+    rt.run_until_idle();
 
     assert_eq!(
         *counter.lock().unwrap(),
@@ -74,6 +76,7 @@ fn test_global_spawn_nested() {
             v3.lock().unwrap().push(4);
         });
     });
+    rt.run_until_idle();
 
     let mut vals = values.lock().unwrap().clone();
     vals.sort();
@@ -97,6 +100,7 @@ fn test_global_spawn_from_spawned_task() {
             });
         });
     });
+    rt.run_until_idle();
 
     assert_eq!(*counter.lock().unwrap(), 11, "Nested spawn should work");
 }
@@ -128,6 +132,7 @@ fn test_global_spawn_with_return_values() {
             r2.lock().unwrap().push(value);
         });
     });
+    rt.run_until_idle();
 
     let mut res = results.lock().unwrap().clone();
     res.sort();
@@ -147,6 +152,7 @@ fn test_spawn_from_separate_async_function() {
     rt.block_on(async move {
         do_work_with_spawn(c).await;
     });
+    rt.run_until_idle();
 
     assert_eq!(
         *counter.lock().unwrap(),
@@ -178,6 +184,7 @@ fn test_spawn_from_nested_function_calls() {
         v.lock().unwrap().push(1);
         nested_function_a(v).await;
     });
+    rt.run_until_idle();
 
     let mut vals = values.lock().unwrap().clone();
     vals.sort();
@@ -0,0 +1,91 @@
+use cadentis::{Handle, RuntimeBuilder, Task};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn assert_send_sync_clone<T: Send + Sync + Clone>() {}
+
+#[test]
+fn test_handle_is_send_sync_clone() {
+    assert_send_sync_clone::<Handle>();
+}
+
+#[test]
+fn test_handle_spawn_from_foreign_thread() {
+    let rt = RuntimeBuilder::new().build();
+    let handle = rt.handle().clone();
+
+    let join = std::thread::spawn(move || handle.spawn(async { 21 * 2 }))
+        .join()
+        .unwrap();
+
+    let result = rt.block_on(join);
+
+    assert_eq!(result, 42, "Task spawned from another thread should run");
+}
+
+#[test]
+fn test_handle_block_on_from_foreign_thread() {
+    let rt = RuntimeBuilder::new().build();
+    let handle = rt.handle().clone();
+
+    let result =
+        std::thread::spawn(move || handle.block_on(async { Task::spawn(async { 7 }).await + 1 }))
+            .join()
+            .unwrap();
+
+    assert_eq!(result, 8);
+}
+
+#[test]
+fn test_handle_enter_installs_context() {
+    let rt = RuntimeBuilder::new().build();
+    let handle = rt.handle().clone();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let counter_clone = counter.clone();
+
+    let join = std::thread::spawn(move || {
+        assert!(Handle::try_current().is_none());
+
+        let join = {
+            let _guard = handle.enter();
+
+            assert!(Handle::try_current().is_some());
+
+            Task::spawn(async move {
+                counter_clone.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+
+        assert!(
+            Handle::try_current().is_none(),
+            "Dropping the guard should restore the previous context"
+        );
+
+        join
+    })
+    .join()
+    .unwrap();
+
+    rt.block_on(join);
+
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_handle_current_inside_runtime() {
+    let rt = RuntimeBuilder::new().build();
+
+    let value = rt.block_on(async {
+        let handle = Handle::current();
+        handle.spawn(async { 5 }).await
+    });
+
+    assert_eq!(value, 5);
+    assert!(Handle::try_current().is_none());
+}
+
+#[test]
+#[should_panic(expected = "Handle::current() called outside of a runtime context")]
+fn test_handle_current_panics_outside_runtime() {
+    Handle::current();
+}
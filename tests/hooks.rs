@@ -0,0 +1,130 @@
+use cadentis::task::{Id, yield_now};
+use cadentis::{RuntimeBuilder, Task};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    Spawn(Id),
+    PollStart(Id),
+    PollEnd(Id),
+    Terminate(Id),
+}
+
+fn recording_runtime(events: &Arc<Mutex<Vec<Event>>>) -> cadentis::Runtime {
+    let (spawn, start, end, terminate) = (
+        events.clone(),
+        events.clone(),
+        events.clone(),
+        events.clone(),
+    );
+
+    RuntimeBuilder::new()
+        .on_task_spawn(move |id| spawn.lock().unwrap().push(Event::Spawn(id)))
+        .on_task_poll_start(move |id| start.lock().unwrap().push(Event::PollStart(id)))
+        .on_task_poll_end(move |id, _| end.lock().unwrap().push(Event::PollEnd(id)))
+        .on_task_terminate(move |id| terminate.lock().unwrap().push(Event::Terminate(id)))
+        .build()
+}
+
+#[test]
+fn test_hooks_follow_task_lifecycle() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let rt = recording_runtime(&events);
+
+    let id = rt.block_on(async {
+        let handle = Task::spawn(async {
+            yield_now().await;
+        });
+        let id = handle.id();
+        handle.await;
+        id
+    });
+
+    let events = events.lock().unwrap();
+    let task_events: Vec<_> = events
+        .iter()
+        .filter(|e| match e {
+            Event::Spawn(i) | Event::PollStart(i) | Event::PollEnd(i) | Event::Terminate(i) => {
+                *i == id
+            }
+        })
+        .collect();
+
+    assert_eq!(
+        task_events,
+        vec![
+            &Event::Spawn(id),
+            &Event::PollStart(id),
+            &Event::PollEnd(id),
+            &Event::PollStart(id),
+            &Event::PollEnd(id),
+            &Event::Terminate(id),
+        ]
+    );
+}
+
+#[test]
+fn test_poll_end_reports_duration() {
+    let longest = Arc::new(Mutex::new(Duration::ZERO));
+    let longest_clone = longest.clone();
+
+    let rt = RuntimeBuilder::new()
+        .on_task_poll_end(move |_, elapsed| {
+            let mut longest = longest_clone.lock().unwrap();
+            *longest = (*longest).max(elapsed);
+        })
+        .build();
+
+    rt.block_on(async {
+        Task::spawn(async {
+            std::thread::sleep(Duration::from_millis(20));
+        })
+        .await;
+    });
+
+    assert!(*longest.lock().unwrap() >= Duration::from_millis(20));
+}
+
+#[test]
+fn test_terminate_hook_runs_for_aborted_and_panicked_tasks() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let rt = recording_runtime(&events);
+
+    let (aborted, panicked) = rt.block_on(async {
+        let aborted = Task::spawn(std::future::pending::<()>());
+        let aborted_id = aborted.id();
+        aborted.abort();
+
+        let panicked = Task::spawn(async { panic!("boom") });
+        let panicked_id = panicked.id();
+        drop(panicked);
+
+        while !events
+            .lock()
+            .unwrap()
+            .contains(&Event::Terminate(panicked_id))
+        {
+            yield_now().await;
+        }
+
+        (aborted_id, panicked_id)
+    });
+
+    let events = events.lock().unwrap();
+    assert!(events.contains(&Event::Terminate(aborted)));
+    assert!(events.contains(&Event::Terminate(panicked)));
+}
+
+#[test]
+fn test_terminate_hook_runs_for_tasks_cancelled_at_shutdown() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let rt = recording_runtime(&events);
+
+    let handle = rt.handle().spawn(std::future::pending::<()>());
+    let id = handle.id();
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+
+    assert!(events.lock().unwrap().contains(&Event::Terminate(id)));
+}
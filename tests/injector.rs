@@ -0,0 +1,128 @@
+use cadentis::{RuntimeBuilder, Task};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+fn wait_for(mut condition: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(10);
+
+    while Instant::now() < deadline {
+        if condition() {
+            return true;
+        }
+
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    false
+}
+
+#[test]
+fn test_spawn_from_many_threads_runs_every_task() {
+    const THREADS: usize = 8;
+    const TASKS: usize = 20_000;
+
+    let rt = RuntimeBuilder::new().worker_threads(4).build();
+    let done = Arc::new(AtomicUsize::new(0));
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            let handle = rt.handle().clone();
+            let done = done.clone();
+
+            scope.spawn(move || {
+                for _ in 0..TASKS {
+                    let done = done.clone();
+                    handle.spawn(async move {
+                        done.fetch_add(1, Ordering::Relaxed);
+                    });
+                }
+            });
+        }
+    });
+
+    assert!(wait_for(|| done.load(Ordering::Relaxed) == THREADS * TASKS));
+}
+
+#[test]
+fn test_nested_spawns_and_yields() {
+    let rt = RuntimeBuilder::new().worker_threads(4).build();
+
+    let total = rt.block_on(async {
+        let parents: Vec<_> = (0..100)
+            .map(|_| {
+                Task::spawn(async {
+                    let children: Vec<_> = (0..100)
+                        .map(|i| {
+                            Task::spawn(async move {
+                                for _ in 0..i % 4 {
+                                    cadentis::yield_now().await;
+                                }
+                                1
+                            })
+                        })
+                        .collect();
+
+                    let mut sum = 0;
+                    for child in children {
+                        sum += child.await;
+                    }
+                    sum
+                })
+            })
+            .collect();
+
+        let mut total = 0;
+        for parent in parents {
+            total += parent.await;
+        }
+        total
+    });
+
+    assert_eq!(total, 100 * 100);
+}
+
+#[test]
+fn test_queued_tasks_spread_across_workers() {
+    const WORKERS: usize = 4;
+
+    let rt = RuntimeBuilder::new().worker_threads(WORKERS).build();
+    let release = Arc::new(AtomicBool::new(false));
+    let started = Arc::new(AtomicUsize::new(0));
+
+    // Keep every worker busy so that the tasks below pile up in the shared queue.
+    for _ in 0..WORKERS {
+        let release = release.clone();
+        let started = started.clone();
+
+        rt.spawn(async move {
+            started.fetch_add(1, Ordering::SeqCst);
+            while !release.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        });
+    }
+
+    assert!(wait_for(|| started.load(Ordering::SeqCst) == WORKERS));
+
+    let done = Arc::new(AtomicUsize::new(0));
+    for _ in 0..200 {
+        let done = done.clone();
+        rt.spawn(async move {
+            std::thread::sleep(Duration::from_millis(1));
+            done.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+
+    assert_eq!(rt.handle().metrics().injector_queue_depth(), 200);
+    release.store(true, Ordering::SeqCst);
+
+    assert!(wait_for(|| done.load(Ordering::SeqCst) == 200));
+
+    let metrics = rt.handle().metrics();
+    let polling = (0..WORKERS)
+        .filter(|&w| metrics.worker_poll_count(w) > 1)
+        .count();
+
+    assert!(polling > 1, "only {} worker ran the queued tasks", polling);
+}
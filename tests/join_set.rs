@@ -0,0 +1,193 @@
+use cadentis::task::JoinSet;
+use cadentis::time::sleep;
+use cadentis::{RuntimeBuilder, Task};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_join_set_yields_in_completion_order() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    let order = rt.block_on(async {
+        let mut set = JoinSet::new();
+
+        for (value, delay) in [(1, 60), (2, 10), (3, 30)] {
+            set.spawn(async move {
+                sleep(Duration::from_millis(delay)).await;
+                value
+            });
+        }
+
+        let mut order = Vec::new();
+        while let Some(value) = set.join_next().await {
+            order.push(value.unwrap());
+        }
+
+        order
+    });
+
+    assert_eq!(order, vec![2, 3, 1]);
+}
+
+#[test]
+fn test_join_set_len_tracks_tasks() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    rt.block_on(async {
+        let mut set = JoinSet::new();
+
+        assert!(set.is_empty());
+
+        set.spawn(async { 1 });
+        set.spawn(async { 2 });
+
+        assert_eq!(set.len(), 2);
+
+        set.join_next().await;
+
+        assert_eq!(set.len(), 1);
+
+        set.join_next().await;
+
+        assert!(set.join_next().await.is_none());
+    });
+}
+
+#[test]
+fn test_join_set_abort_all_drops_futures() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+    let dropped = Arc::new(AtomicBool::new(false));
+    let flag = DropFlag(dropped.clone());
+
+    rt.block_on(async move {
+        let mut set = JoinSet::new();
+
+        set.spawn(async move {
+            let _flag = flag;
+            std::future::pending::<()>().await;
+        });
+
+        sleep(Duration::from_millis(20)).await;
+        set.abort_all();
+
+        assert!(set.is_empty());
+        assert!(set.join_next().await.is_none());
+    });
+
+    rt.run_until_idle();
+
+    assert!(
+        dropped.load(Ordering::SeqCst),
+        "Aborted future should be dropped"
+    );
+}
+
+#[test]
+fn test_join_set_drop_aborts_tasks() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+    let dropped = Arc::new(AtomicBool::new(false));
+    let flag = DropFlag(dropped.clone());
+
+    rt.block_on(async move {
+        let mut set = JoinSet::new();
+
+        set.spawn(async move {
+            let _flag = flag;
+            sleep(Duration::from_secs(60)).await;
+        });
+
+        sleep(Duration::from_millis(20)).await;
+    });
+
+    rt.run_until_idle();
+
+    assert!(
+        dropped.load(Ordering::SeqCst),
+        "Dropping the set should abort its tasks"
+    );
+}
+
+#[test]
+fn test_join_set_yields_error_for_aborted_task() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    rt.block_on(async {
+        let mut set = JoinSet::new();
+
+        let pending = set.spawn(std::future::pending::<u32>());
+        set.spawn(async { 7 });
+
+        set.abort(pending);
+
+        let mut values = Vec::new();
+        let mut errors = Vec::new();
+
+        while let Some(result) = set.join_next().await {
+            match result {
+                Ok(value) => values.push(value),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        assert_eq!(values, vec![7]);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].is_cancelled());
+        assert_eq!(errors[0].id(), pending);
+        assert!(errors[0].to_string().contains("was cancelled"));
+    });
+}
+
+#[test]
+fn test_join_set_yields_error_for_panicked_task() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    let error = rt.block_on(async {
+        let mut set = JoinSet::<()>::new();
+
+        set.spawn(async { panic!("boom") });
+
+        set.join_next().await.unwrap().unwrap_err()
+    });
+
+    assert!(error.is_panic());
+    assert!(!error.is_cancelled());
+    assert!(error.to_string().contains("boom"));
+}
+
+#[test]
+fn test_join_set_yields_error_for_task_cancelled_at_shutdown() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+    let other = RuntimeBuilder::new().enable_io().build();
+
+    let mut set = rt.block_on(async {
+        let mut set = JoinSet::new();
+        set.spawn(std::future::pending::<()>());
+        set
+    });
+
+    drop(rt);
+
+    let result = other.block_on(set.join_next()).unwrap();
+    assert!(result.unwrap_err().is_cancelled());
+}
+
+#[test]
+#[should_panic(expected = "was cancelled")]
+fn test_awaiting_aborted_join_handle_panics() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    rt.block_on(async {
+        let handle = Task::spawn(std::future::pending::<()>());
+        handle.abort();
+        handle.await;
+    });
+}
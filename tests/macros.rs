@@ -0,0 +1,45 @@
+use cadentis::Task;
+use cadentis::time::{Instant, sleep};
+use std::time::Duration;
+
+#[cadentis::test]
+async fn test_macro_runs_body() {
+    let value = Task::spawn(async { 21 * 2 }).await;
+
+    assert_eq!(value, 42);
+}
+
+#[cadentis::test(io, start_paused = true)]
+async fn test_macro_start_paused() {
+    let start = Instant::now();
+    let wall = std::time::Instant::now();
+
+    sleep(Duration::from_secs(60)).await;
+
+    assert!(start.elapsed() >= Duration::from_secs(60));
+    assert!(wall.elapsed() < Duration::from_secs(5));
+}
+
+#[cadentis::test(flavor = "current_thread", io)]
+async fn test_macro_current_thread() {
+    let caller = std::thread::current().id();
+
+    let polled_on = Task::spawn(async { std::thread::current().id() }).await;
+
+    assert_eq!(polled_on, caller);
+}
+
+#[cadentis::test(worker_threads = 2)]
+async fn test_macro_worker_threads() {
+    let workers = cadentis::Handle::current().metrics().num_workers();
+
+    assert_eq!(workers, 2);
+}
+
+#[cadentis::test]
+async fn test_macro_returns_result() -> Result<(), String> {
+    let value: Result<u8, String> = Task::spawn(async { Ok(1) }).await;
+
+    assert_eq!(value?, 1);
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use cadentis::net::tcp_listener::TcpListener;
+use cadentis::time::sleep;
+use cadentis::{RuntimeBuilder, Task};
+use std::net::TcpStream as StdTcpStream;
+use std::time::Duration;
+
+fn num_workers() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+#[test]
+fn test_metrics_count_spawned_and_completed_tasks() {
+    let rt = RuntimeBuilder::new().build();
+
+    rt.block_on(async {
+        for _ in 0..10 {
+            Task::spawn(async {}).await;
+        }
+    });
+
+    let metrics = rt.handle().metrics();
+
+    assert_eq!(metrics.num_workers(), num_workers());
+    assert_eq!(metrics.spawned_tasks_count(), 10);
+    assert_eq!(metrics.completed_tasks_count(), 10);
+    assert_eq!(metrics.alive_tasks_count(), 0);
+    assert!(metrics.total_poll_count() >= 10);
+    assert_eq!(metrics.injector_queue_depth(), 0);
+}
+
+#[test]
+fn test_metrics_worker_counters_add_up() {
+    let rt = RuntimeBuilder::new().build();
+
+    rt.block_on(async {
+        let handles: Vec<_> = (0..50).map(|_| Task::spawn(async {})).collect();
+        for handle in handles {
+            handle.await;
+        }
+    });
+
+    let metrics = rt.handle().metrics();
+    let worker_polls: u64 = (0..metrics.num_workers())
+        .map(|w| metrics.worker_poll_count(w))
+        .sum();
+
+    assert!(
+        worker_polls <= metrics.total_poll_count(),
+        "Worker polls are a subset of all polls"
+    );
+
+    for worker in 0..metrics.num_workers() {
+        assert_eq!(metrics.worker_local_queue_depth(worker), 0);
+        assert!(metrics.worker_unpark_count(worker) <= metrics.worker_park_count(worker));
+    }
+}
+
+#[test]
+fn test_metrics_report_pending_timers() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+    let handle = rt.handle().clone();
+
+    let pending = rt.block_on(async move {
+        let sleeper = Task::spawn(sleep(Duration::from_secs(60)));
+
+        while handle.metrics().pending_timer_count() == 0 {
+            cadentis::yield_now().await;
+        }
+
+        let pending = handle.metrics();
+        sleeper.abort();
+
+        pending
+    });
+
+    assert_eq!(pending.pending_timer_count(), 1);
+    assert_eq!(pending.alive_tasks_count(), 1);
+    assert!(pending.reactor_turn_count() > 0);
+}
+
+#[test]
+fn test_metrics_count_reactor_events() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let accept = Task::spawn(async move { listener.accept().await.is_ok() });
+
+        // Connect once the accept waits on the reactor, so readiness is an event.
+        sleep(Duration::from_millis(20)).await;
+        let client = std::thread::spawn(move || StdTcpStream::connect(address).unwrap());
+
+        assert!(accept.await);
+        client.join().unwrap();
+    });
+
+    let metrics = rt.handle().metrics();
+
+    assert!(
+        metrics.reactor_event_count() >= 1,
+        "An incoming connection is an event"
+    );
+    assert!(metrics.reactor_events_last_turn() >= 1);
+    assert_eq!(metrics.pending_timer_count(), 0);
+}
@@ -0,0 +1,88 @@
+use cadentis::task::{block_in_place, spawn_blocking};
+use cadentis::{Handle, Runtime, RuntimeBuilder, Task};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::mpsc;
+use std::time::Duration;
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_default()
+}
+
+#[test]
+#[should_panic(expected = "cannot block_on from within a runtime")]
+fn test_block_on_inside_block_on_panics() {
+    let rt = RuntimeBuilder::new().build();
+
+    rt.block_on(async {
+        Handle::current().block_on(async {});
+    });
+}
+
+#[test]
+fn test_block_on_other_runtime_from_task_panics() {
+    let rt = RuntimeBuilder::new().build();
+    let (tx, rx) = mpsc::channel();
+
+    rt.spawn(async move {
+        let other = RuntimeBuilder::new().worker_threads(1).build();
+        let result = catch_unwind(AssertUnwindSafe(|| other.block_on(async { 1 })));
+
+        tx.send(result.map_err(panic_message)).unwrap();
+    });
+
+    let result = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    let message = result.unwrap_err();
+
+    assert!(message.contains("cannot block_on from within a runtime"));
+}
+
+#[test]
+fn test_spawn_blocking_drives_another_runtime() {
+    let rt = RuntimeBuilder::new().build();
+
+    let value = rt.block_on(async {
+        spawn_blocking(|| {
+            let other = RuntimeBuilder::new().worker_threads(1).build();
+
+            other.block_on(async { Task::spawn(async { 40 + 2 }).await })
+        })
+        .await
+    });
+
+    assert_eq!(value, 42);
+}
+
+#[test]
+fn test_block_in_place_drives_another_runtime() {
+    let rt = RuntimeBuilder::new().build();
+    let other = RuntimeBuilder::new().worker_threads(1).build();
+
+    let value = rt.block_on(async {
+        Task::spawn(async move {
+            block_in_place(|| other.block_on(async { Task::spawn(async { 7 }).await }))
+        })
+        .await
+    });
+
+    assert_eq!(value, 7);
+}
+
+#[test]
+fn test_block_on_after_block_on_returns() {
+    let rt = Runtime::default();
+
+    assert_eq!(rt.block_on(async { 1 }), 1);
+    assert_eq!(rt.block_on(async { 2 }), 2);
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        rt.block_on(async { Handle::current().block_on(async {}) })
+    }));
+    assert!(result.is_err());
+
+    // The failed nested call does not leave the thread marked as running tasks.
+    assert_eq!(rt.block_on(async { 3 }), 3);
+}
@@ -0,0 +1,170 @@
+use cadentis::net::tcp_listener::TcpListener;
+use cadentis::task::spawn_blocking;
+use cadentis::time::sleep;
+use cadentis::{RuntimeBuilder, RuntimeMetrics};
+use std::io::Write;
+use std::net::TcpStream as StdTcpStream;
+use std::sync::mpsc;
+use std::time::Duration;
+
+fn total(metrics: &RuntimeMetrics, count: impl Fn(&RuntimeMetrics, usize) -> u64) -> u64 {
+    (0..metrics.num_workers()).map(|w| count(metrics, w)).sum()
+}
+
+/// Waits until every worker has parked again after the last activity.
+fn settle() {
+    std::thread::sleep(Duration::from_millis(50));
+}
+
+#[test]
+fn test_idle_workers_stay_parked() {
+    let rt = RuntimeBuilder::new().worker_threads(4).build();
+    settle();
+
+    let before = rt.handle().metrics();
+    std::thread::sleep(Duration::from_millis(100));
+    let after = rt.handle().metrics();
+
+    assert_eq!(
+        total(&before, RuntimeMetrics::worker_park_count),
+        total(&after, RuntimeMetrics::worker_park_count)
+    );
+}
+
+#[test]
+fn test_one_task_wakes_few_workers() {
+    let rt = RuntimeBuilder::new().worker_threads(8).build();
+    settle();
+
+    let before = rt.handle().metrics();
+    let (tx, rx) = mpsc::channel();
+
+    rt.spawn(async move {
+        tx.send(()).unwrap();
+    });
+
+    rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    settle();
+
+    let after = rt.handle().metrics();
+    let woken = total(&after, RuntimeMetrics::worker_unpark_count)
+        - total(&before, RuntimeMetrics::worker_unpark_count);
+
+    // The notified worker, plus one more taking over the reactor if needed.
+    assert!((1..=2).contains(&woken), "{} workers woken", woken);
+}
+
+#[test]
+fn test_parked_workers_wake_for_timers() {
+    let rt = RuntimeBuilder::new().enable_io().worker_threads(2).build();
+    let (tx, rx) = mpsc::channel();
+
+    rt.spawn(async move {
+        sleep(Duration::from_millis(30)).await;
+        tx.send(()).unwrap();
+    });
+
+    rx.recv_timeout(Duration::from_secs(5)).unwrap();
+}
+
+#[test]
+fn test_unparks_are_not_reactor_events() {
+    let rt = RuntimeBuilder::new().enable_io().worker_threads(2).build();
+    settle();
+
+    for _ in 0..20 {
+        let (tx, rx) = mpsc::channel();
+
+        rt.spawn(async move {
+            tx.send(()).unwrap();
+        });
+
+        rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    let metrics = rt.handle().metrics();
+
+    assert!(total(&metrics, RuntimeMetrics::worker_unpark_count) > 0);
+    assert_eq!(metrics.reactor_event_count(), 0);
+}
+
+#[test]
+fn test_unread_data_does_not_wake_parked_workers() {
+    let rt = RuntimeBuilder::new().enable_io().worker_threads(1).build();
+    let (port_tx, port_rx) = mpsc::channel();
+    let (read_tx, read_rx) = mpsc::channel();
+
+    // Reads one byte of the ten sent, then waits with the rest left unread.
+    rt.spawn(async move {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        port_tx.send(listener.local_addr().unwrap().port()).unwrap();
+
+        let (stream, _peer) = listener.accept().await.unwrap();
+        let mut byte = [0u8; 1];
+        stream.read(&mut byte).await.unwrap();
+        read_tx.send(()).unwrap();
+
+        sleep(Duration::from_secs(60)).await;
+    });
+
+    let port = port_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    let mut client = StdTcpStream::connect(("127.0.0.1", port)).unwrap();
+
+    // Lets the read register its interest before any data arrives.
+    settle();
+    client.write_all(&[1; 10]).unwrap();
+
+    read_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    settle();
+
+    let before = rt.handle().metrics();
+    std::thread::sleep(Duration::from_millis(100));
+    let after = rt.handle().metrics();
+
+    let polls = after.total_poll_count() - before.total_poll_count();
+    let parks = total(&after, RuntimeMetrics::worker_park_count)
+        - total(&before, RuntimeMetrics::worker_park_count);
+    let turns = after.reactor_turn_count() - before.reactor_turn_count();
+
+    assert_eq!(polls, 0, "sleeping task polled {} times", polls);
+    assert!(parks <= 1, "worker parked {} times while idle", parks);
+    assert!(turns <= 2, "reactor turned {} times while idle", turns);
+}
+
+#[test]
+fn test_block_on_sleeps_while_its_future_is_pending() {
+    let rt = RuntimeBuilder::new().enable_io().worker_threads(1).build();
+    settle();
+
+    let before = rt.handle().metrics();
+    rt.block_on(async { sleep(Duration::from_millis(100)).await });
+    let after = rt.handle().metrics();
+
+    // One turn per wakeup of `block_on`, plus the worker's.
+    let turns = after.reactor_turn_count() - before.reactor_turn_count();
+    assert!(turns < 20, "reactor turned {} times", turns);
+}
+
+#[test]
+fn test_block_on_without_workers_waits_in_the_reactor() {
+    let rt = RuntimeBuilder::new().enable_io().worker_threads(0).build();
+
+    let before = rt.handle().metrics();
+    let value = rt.block_on(async {
+        sleep(Duration::from_millis(50)).await;
+
+        // Finishing on another thread queues the task, which wakes `block_on`.
+        spawn_blocking(|| {
+            std::thread::sleep(Duration::from_millis(50));
+            5
+        })
+        .await
+    });
+    let after = rt.handle().metrics();
+
+    assert_eq!(value, 5);
+
+    let turns = after.reactor_turn_count() - before.reactor_turn_count();
+    assert!(turns < 20, "reactor turned {} times", turns);
+}
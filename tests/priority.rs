@@ -0,0 +1,123 @@
+use cadentis::RuntimeBuilder;
+use cadentis::task::{Builder, Priority};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn num_workers() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Occupies every worker until `release` is set.
+fn block_workers(rt: &cadentis::Runtime, release: &Arc<AtomicBool>) {
+    let started = Arc::new(AtomicUsize::new(0));
+
+    for _ in 0..num_workers() {
+        let started = started.clone();
+        let release = release.clone();
+
+        rt.spawn(async move {
+            started.fetch_add(1, Ordering::SeqCst);
+            while !release.load(Ordering::SeqCst) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        });
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while started.load(Ordering::SeqCst) < num_workers() {
+        assert!(
+            Instant::now() < deadline,
+            "Workers never picked up blockers"
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn test_queue_depth_per_priority() {
+    let rt = RuntimeBuilder::new().build();
+    let release = Arc::new(AtomicBool::new(false));
+    block_workers(&rt, &release);
+
+    let handles = {
+        let _guard = rt.handle().enter();
+
+        let mut handles = Vec::new();
+        for _ in 0..3 {
+            handles.push(Builder::new().priority(Priority::High).spawn(async {}));
+        }
+        for _ in 0..5 {
+            handles.push(
+                Builder::new()
+                    .priority(Priority::Background)
+                    .spawn(async {}),
+            );
+        }
+
+        handles
+    };
+
+    let handle = rt.handle();
+    assert_eq!(handle.queue_depth(Priority::High), 3);
+    assert_eq!(handle.queue_depth(Priority::Normal), 0);
+    assert_eq!(handle.queue_depth(Priority::Background), 5);
+
+    release.store(true, Ordering::SeqCst);
+
+    rt.block_on(async {
+        for h in handles {
+            h.await;
+        }
+    });
+
+    assert_eq!(handle.queue_depth(Priority::Background), 0);
+}
+
+#[test]
+fn test_high_priority_runs_before_background_without_starving_it() {
+    let rt = RuntimeBuilder::new().build();
+    let release = Arc::new(AtomicBool::new(false));
+    let order = Arc::new(Mutex::new(Vec::new()));
+    block_workers(&rt, &release);
+
+    let handles = {
+        let _guard = rt.handle().enter();
+
+        let mut handles = Vec::new();
+        for _ in 0..20 {
+            for priority in [Priority::Background, Priority::High] {
+                let order = order.clone();
+                handles.push(
+                    Builder::new()
+                        .priority(priority)
+                        .spawn(async move { order.lock().unwrap().push(priority) }),
+                );
+            }
+        }
+
+        handles
+    };
+
+    release.store(true, Ordering::SeqCst);
+
+    rt.block_on(async {
+        for h in handles {
+            h.await;
+        }
+    });
+
+    let order = order.lock().unwrap();
+    let mean = |p: Priority| {
+        let positions: Vec<_> = (0..order.len()).filter(|&i| order[i] == p).collect();
+        assert_eq!(positions.len(), 20, "Every task should run");
+        positions.iter().sum::<usize>() as f64 / positions.len() as f64
+    };
+
+    assert!(
+        mean(Priority::High) < mean(Priority::Background),
+        "High priority tasks should be picked first on average"
+    );
+}
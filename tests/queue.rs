@@ -0,0 +1,231 @@
+use cadentis::__private::{BLOCK_CAP, SegQueue};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier, mpsc};
+
+/// Counts its drops, so leaked or doubly dropped values show up.
+struct Tracked(usize, Arc<AtomicUsize>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.1.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn batch(queue: &SegQueue<usize>, max: usize) -> Vec<usize> {
+    let mut values = Vec::new();
+    let popped = queue.pop_batch(max, |v| values.push(v));
+    assert_eq!(popped, values.len());
+
+    values
+}
+
+#[test]
+fn test_batch_ending_at_block_end() {
+    let queue = SegQueue::new();
+
+    for i in 0..BLOCK_CAP * 2 + 5 {
+        queue.push(i);
+    }
+
+    assert_eq!(batch(&queue, BLOCK_CAP), (0..BLOCK_CAP).collect::<Vec<_>>());
+    assert_eq!(queue.len(), BLOCK_CAP + 5);
+
+    assert_eq!(
+        batch(&queue, BLOCK_CAP),
+        (BLOCK_CAP..BLOCK_CAP * 2).collect::<Vec<_>>()
+    );
+    assert_eq!(queue.len(), 5);
+
+    assert_eq!(
+        batch(&queue, BLOCK_CAP),
+        (BLOCK_CAP * 2..BLOCK_CAP * 2 + 5).collect::<Vec<_>>()
+    );
+    assert!(queue.is_empty());
+    assert_eq!(batch(&queue, BLOCK_CAP), Vec::<usize>::new());
+}
+
+#[test]
+fn test_batch_stops_at_block_end() {
+    let queue = SegQueue::new();
+
+    for i in 0..BLOCK_CAP * 2 {
+        queue.push(i);
+    }
+
+    assert_eq!(batch(&queue, 10), (0..10).collect::<Vec<_>>());
+    assert_eq!(batch(&queue, 100), (10..BLOCK_CAP).collect::<Vec<_>>());
+    assert_eq!(queue.pop(), Some(BLOCK_CAP));
+    assert_eq!(queue.len(), BLOCK_CAP - 1);
+}
+
+#[test]
+fn test_batch_ending_at_tail_of_full_block() {
+    let queue = SegQueue::new();
+
+    // Filling the block exactly installs the next one.
+    for i in 0..BLOCK_CAP {
+        queue.push(i);
+    }
+
+    assert_eq!(
+        batch(&queue, BLOCK_CAP + 1),
+        (0..BLOCK_CAP).collect::<Vec<_>>()
+    );
+    assert!(queue.is_empty());
+
+    queue.push(BLOCK_CAP);
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.pop(), Some(BLOCK_CAP));
+}
+
+#[test]
+fn test_block_destroyed_from_the_middle_of_a_batch() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    let queue = SegQueue::new();
+
+    for i in 0..BLOCK_CAP + 1 {
+        queue.push(Tracked(i, drops.clone()));
+    }
+
+    let (claimed_tx, claimed_rx) = mpsc::channel();
+    let (last_tx, last_rx) = mpsc::channel::<()>();
+
+    std::thread::scope(|scope| {
+        let queue = &queue;
+
+        let batcher = scope.spawn(move || {
+            let mut seen = Vec::new();
+
+            // Claims every slot but the block's last one, then stalls after
+            // the first value until another consumer has read that last slot.
+            queue.pop_batch(BLOCK_CAP - 1, |v| {
+                if seen.is_empty() {
+                    claimed_tx.send(()).unwrap();
+                    last_rx.recv().unwrap();
+                }
+
+                seen.push(v.0);
+            });
+
+            seen
+        });
+
+        claimed_rx.recv().unwrap();
+
+        // Reading the last slot asks the stalled batch to free the block.
+        let last = queue.pop().unwrap();
+        assert_eq!(last.0, BLOCK_CAP - 1);
+        last_tx.send(()).unwrap();
+
+        let seen = batcher.join().unwrap();
+        assert_eq!(seen, (0..BLOCK_CAP - 1).collect::<Vec<_>>());
+    });
+
+    assert_eq!(queue.pop().map(|v| v.0), Some(BLOCK_CAP));
+    assert!(queue.is_empty());
+    assert_eq!(drops.load(Ordering::Relaxed), BLOCK_CAP + 1);
+}
+
+#[test]
+fn test_len_across_blocks() {
+    let queue = SegQueue::new();
+    let total = BLOCK_CAP * 3 + 7;
+
+    for i in 0..total {
+        assert_eq!(queue.len(), i);
+        queue.push(i);
+    }
+
+    assert_eq!(queue.len(), total);
+
+    let mut remaining = total;
+
+    while remaining > 0 {
+        let popped = batch(&queue, 4).len();
+        assert!(popped > 0);
+
+        remaining -= popped;
+        assert_eq!(queue.len(), remaining);
+        assert_eq!(queue.is_empty(), remaining == 0);
+    }
+
+    // Indices keep counting across laps once the queue was drained.
+    for i in 0..BLOCK_CAP + 2 {
+        queue.push(i);
+        assert_eq!(queue.len(), i + 1);
+    }
+}
+
+#[test]
+fn test_drop_with_values_queued() {
+    let drops = Arc::new(AtomicUsize::new(0));
+
+    for popped in [0, 5, BLOCK_CAP, BLOCK_CAP + 3] {
+        let queue = SegQueue::new();
+        let total = BLOCK_CAP * 2 + 4;
+
+        for i in 0..total {
+            queue.push(Tracked(i, drops.clone()));
+        }
+
+        for _ in 0..popped {
+            drop(queue.pop());
+        }
+
+        drops.store(0, Ordering::Relaxed);
+        drop(queue);
+
+        assert_eq!(drops.load(Ordering::Relaxed), total - popped);
+    }
+
+    // Empty and never used.
+    drop(SegQueue::<Tracked>::new());
+}
+
+#[test]
+fn test_concurrent_producers_and_batch_consumers() {
+    const THREADS: usize = 4;
+    let per_thread = if cfg!(miri) { 50 } else { 10_000 };
+    let total = THREADS * per_thread;
+
+    let queue = SegQueue::new();
+    let popped = AtomicUsize::new(0);
+    let seen: Vec<AtomicUsize> = (0..total).map(|_| AtomicUsize::new(0)).collect();
+    let start = Barrier::new(THREADS * 2);
+
+    std::thread::scope(|scope| {
+        for t in 0..THREADS {
+            let (queue, start) = (&queue, &start);
+
+            scope.spawn(move || {
+                start.wait();
+
+                for i in 0..per_thread {
+                    queue.push(t * per_thread + i);
+                }
+            });
+        }
+
+        for t in 0..THREADS {
+            let (queue, start, popped, seen) = (&queue, &start, &popped, &seen);
+
+            scope.spawn(move || {
+                start.wait();
+
+                while popped.load(Ordering::Relaxed) < total {
+                    let n = queue.pop_batch(t + 1, |v| {
+                        seen[v].fetch_add(1, Ordering::Relaxed);
+                    });
+                    popped.fetch_add(n, Ordering::Relaxed);
+
+                    if n == 0 {
+                        std::thread::yield_now();
+                    }
+                }
+            });
+        }
+    });
+
+    assert!(seen.iter().all(|n| n.load(Ordering::Relaxed) == 1));
+    assert!(queue.is_empty());
+}
@@ -108,7 +108,7 @@ fn test_spawn_simple_task() {
         *completed_clone.lock().unwrap() = true;
     });
 
-    rt.block_on(async {});
+    rt.run_until_idle();
 
     assert!(
         *completed.lock().unwrap(),
@@ -129,7 +129,7 @@ fn test_spawn_multiple_tasks() {
         });
     }
 
-    rt.block_on(async {});
+    rt.run_until_idle();
 
     assert_eq!(*counter.lock().unwrap(), 5, "All 5 tasks should have run");
 }
@@ -146,14 +146,14 @@ fn test_spawn_with_shared_state() {
         });
     }
 
-    rt.block_on(async {});
+    rt.run_until_idle();
 
     let final_state = state.lock().unwrap();
     assert_eq!(final_state.len(), 3, "Should have 3 values");
 }
 
 #[test]
-fn test_block_on_waits_for_spawned_tasks() {
+fn test_run_until_idle_waits_for_spawned_tasks() {
     let rt = RuntimeBuilder::new().build();
     let executed = Arc::new(Mutex::new(false));
     let executed_clone = executed.clone();
@@ -162,10 +162,57 @@ fn test_block_on_waits_for_spawned_tasks() {
         *executed_clone.lock().unwrap() = true;
     });
 
-    rt.block_on(async {});
+    rt.run_until_idle();
 
     assert!(
         *executed.lock().unwrap(),
-        "Spawned task should execute before block_on returns"
+        "Spawned task should execute before run_until_idle returns"
+    );
+}
+
+#[test]
+fn test_block_on_does_not_wait_for_detached_tasks() {
+    let rt = RuntimeBuilder::new().build();
+
+    let result = rt.block_on(async {
+        cadentis::Task::spawn(async {
+            loop {
+                cadentis::yield_now().await;
+            }
+        });
+
+        7
+    });
+
+    assert_eq!(
+        result, 7,
+        "block_on should return once its future completes"
+    );
+}
+
+#[test]
+fn test_detached_task_keeps_running_after_block_on() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::{Duration, Instant};
+
+    let rt = RuntimeBuilder::new().enable_io().build();
+    let done = Arc::new(AtomicBool::new(false));
+    let done_clone = done.clone();
+
+    rt.block_on(async move {
+        cadentis::Task::spawn(async move {
+            cadentis::time::sleep(Duration::from_millis(20)).await;
+            done_clone.store(true, Ordering::SeqCst);
+        });
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while !done.load(Ordering::SeqCst) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    assert!(
+        done.load(Ordering::SeqCst),
+        "Detached task should finish on the workers"
     );
 }
@@ -0,0 +1,161 @@
+use cadentis::RuntimeBuilder;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_shutdown_timeout_drops_pending_tasks() {
+    let rt = RuntimeBuilder::new().build();
+    let dropped = Arc::new(AtomicBool::new(false));
+    let flag = DropFlag(dropped.clone());
+
+    rt.spawn(async move {
+        let _flag = flag;
+        std::future::pending::<()>().await;
+    });
+
+    let report = rt.shutdown_timeout(Duration::from_secs(1));
+
+    assert!(report.is_clean(), "Idle workers should be joined");
+    assert_eq!(
+        report.cancelled().len(),
+        1,
+        "Pending task should be reported"
+    );
+    assert!(
+        dropped.load(Ordering::SeqCst),
+        "Pending future should be dropped"
+    );
+}
+
+#[test]
+fn test_shutdown_timeout_reports_running_task() {
+    let rt = RuntimeBuilder::new().build();
+    let started = Arc::new(AtomicBool::new(false));
+    let started_clone = started.clone();
+
+    rt.spawn(async move {
+        started_clone.store(true, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(300));
+    });
+
+    while !started.load(Ordering::SeqCst) {
+        std::thread::yield_now();
+    }
+
+    let start = Instant::now();
+    let report = rt.shutdown_timeout(Duration::from_millis(20));
+
+    assert!(
+        start.elapsed() < Duration::from_millis(250),
+        "Shutdown should not wait past its timeout"
+    );
+    assert!(!report.workers_joined());
+    assert_eq!(
+        report.still_running().len(),
+        1,
+        "Blocked task should be reported as running"
+    );
+}
+
+#[test]
+fn test_shutdown_background_returns_immediately() {
+    let rt = RuntimeBuilder::new().build();
+
+    rt.spawn(async {
+        std::future::pending::<()>().await;
+    });
+
+    let start = Instant::now();
+    rt.shutdown_background();
+
+    assert!(start.elapsed() < Duration::from_millis(100));
+}
+
+#[test]
+fn test_drop_runtime_drops_pending_tasks() {
+    let dropped = Arc::new(AtomicBool::new(false));
+
+    {
+        let rt = RuntimeBuilder::new().enable_io().build();
+        let flag = DropFlag(dropped.clone());
+
+        rt.spawn(async move {
+            let _flag = flag;
+            cadentis::time::sleep(Duration::from_secs(60)).await;
+        });
+    }
+
+    assert!(
+        dropped.load(Ordering::SeqCst),
+        "Dropping the runtime should drop pending futures"
+    );
+}
+
+#[test]
+fn test_drop_runtime_does_not_wait_for_a_stuck_worker() {
+    let rt = RuntimeBuilder::new()
+        .worker_threads(1)
+        .drop_timeout(Duration::from_millis(20))
+        .build();
+    let started = Arc::new(AtomicBool::new(false));
+    let started_clone = started.clone();
+
+    rt.spawn(async move {
+        started_clone.store(true, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(500));
+    });
+
+    while !started.load(Ordering::SeqCst) {
+        std::thread::yield_now();
+    }
+
+    let start = Instant::now();
+    drop(rt);
+
+    assert!(
+        start.elapsed() < Duration::from_millis(400),
+        "Dropping the runtime should not wait past its drop timeout"
+    );
+}
+
+#[test]
+fn test_awaiting_task_cancelled_at_shutdown() {
+    let rt = RuntimeBuilder::new().max_tasks(1).build();
+    let handle = rt.handle().clone();
+    let task = rt.handle().spawn(std::future::pending::<()>());
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let awaiter = std::thread::spawn(move || {
+        let other = RuntimeBuilder::new().worker_threads(1).build();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            other.block_on(async {
+                tx.send(()).unwrap();
+                task.await
+            })
+        }));
+
+        result.is_err()
+    });
+
+    // Let the awaiter register before the task is cancelled.
+    rx.recv().unwrap();
+    std::thread::sleep(Duration::from_millis(20));
+
+    let report = rt.shutdown_timeout(Duration::from_secs(1));
+
+    assert_eq!(report.cancelled().len(), 1);
+    assert!(
+        awaiter.join().unwrap(),
+        "Awaiting a cancelled task should panic rather than hang"
+    );
+    assert_eq!(handle.metrics().alive_tasks_count(), 0);
+}
@@ -0,0 +1,116 @@
+use cadentis::time::{Time, sleep, timeout};
+use cadentis::tools::retry;
+use cadentis::{RuntimeBuilder, Task, yield_now};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Runs a small workload and returns the order in which tasks made progress.
+fn trace(seed: u64) -> Vec<(usize, usize)> {
+    let rt = RuntimeBuilder::new().enable_io().simulation(seed).build();
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    rt.block_on(async {
+        let handles: Vec<_> = (0..8)
+            .map(|task| {
+                let events = events.clone();
+                Task::spawn(async move {
+                    for step in 0..4 {
+                        events.lock().unwrap().push((task, step));
+                        if step % 2 == 0 {
+                            yield_now().await;
+                        } else {
+                            sleep(Duration::from_millis(10)).await;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.await;
+        }
+    });
+
+    events.lock().unwrap().clone()
+}
+
+#[test]
+fn test_same_seed_replays_same_schedule() {
+    assert_eq!(trace(42), trace(42), "A seed should replay exactly");
+}
+
+#[test]
+fn test_different_seeds_explore_different_schedules() {
+    let first = trace(1);
+
+    assert!(
+        (2..10).any(|seed| trace(seed) != first),
+        "Different seeds should produce different interleavings"
+    );
+}
+
+#[test]
+fn test_simulation_runs_tasks_on_calling_thread() {
+    let rt = RuntimeBuilder::new().simulation(7).build();
+    let caller = std::thread::current().id();
+
+    let task_thread =
+        rt.block_on(async { Task::spawn(async { std::thread::current().id() }).await });
+
+    assert_eq!(task_thread, caller);
+    assert_eq!(rt.seed(), Some(7));
+}
+
+#[test]
+fn test_simulation_sleep_uses_virtual_time() {
+    let rt = RuntimeBuilder::new().enable_io().simulation(3).build();
+    let start = Instant::now();
+
+    let (_, elapsed) = rt.block_on(async {
+        Time::new(Task::spawn(async {
+            sleep(Duration::from_secs(3600)).await;
+        }))
+        .await
+    });
+
+    assert!(
+        elapsed >= Duration::from_secs(3600),
+        "Virtual time should pass"
+    );
+    assert!(
+        start.elapsed() < Duration::from_secs(5),
+        "Real time should not pass"
+    );
+}
+
+#[test]
+fn test_simulation_timeout_and_retry() {
+    let rt = RuntimeBuilder::new().enable_io().simulation(11).build();
+    let attempts = Arc::new(Mutex::new(0));
+    let attempts_clone = attempts.clone();
+
+    let (timed_out, retried) = rt.block_on(async move {
+        let timed_out = timeout(Duration::from_secs(1), sleep(Duration::from_secs(60))).await;
+
+        let retried = retry(5, move || {
+            let attempts = attempts_clone.clone();
+            async move {
+                let mut attempts = attempts.lock().unwrap();
+                *attempts += 1;
+                if *attempts < 3 {
+                    Err("fail")
+                } else {
+                    Ok(*attempts)
+                }
+            }
+        })
+        .set_interval(Duration::from_secs(30))
+        .await;
+
+        (timed_out, retried)
+    });
+
+    assert!(timed_out.is_err());
+    assert_eq!(retried, Ok(3));
+    assert_eq!(*attempts.lock().unwrap(), 3);
+}
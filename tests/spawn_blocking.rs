@@ -0,0 +1,83 @@
+use cadentis::task::{block_in_place, spawn_blocking};
+use cadentis::{RuntimeBuilder, Task};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+#[test]
+fn test_spawn_blocking_returns_value() {
+    let rt = RuntimeBuilder::new().build();
+
+    let result = rt.block_on(async { spawn_blocking(|| 6 * 7).await });
+
+    assert_eq!(result, 42, "Blocking closure result should be returned");
+}
+
+#[test]
+fn test_spawn_blocking_runs_off_runtime_thread() {
+    let rt = RuntimeBuilder::new().build();
+    let caller = std::thread::current().id();
+
+    let blocking_thread =
+        rt.block_on(async { spawn_blocking(|| std::thread::current().id()).await });
+
+    assert_ne!(
+        caller, blocking_thread,
+        "Blocking closure should run on a pool thread"
+    );
+}
+
+#[test]
+fn test_spawn_blocking_many_with_limited_pool() {
+    let rt = RuntimeBuilder::new()
+        .max_blocking_threads(2)
+        .thread_keep_alive(Duration::from_millis(50))
+        .build();
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    let total = rt.block_on(async {
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let counter = counter.clone();
+                spawn_blocking(move || {
+                    std::thread::sleep(Duration::from_millis(5));
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    i
+                })
+            })
+            .collect();
+
+        let mut total = 0;
+        for handle in handles {
+            total += handle.await;
+        }
+        total
+    });
+
+    assert_eq!(total, 28, "All blocking closures should complete");
+    assert_eq!(counter.load(Ordering::SeqCst), 8);
+}
+
+#[test]
+#[should_panic(expected = "spawn_blocking() called outside of a runtime context")]
+fn test_spawn_blocking_panics_outside_runtime() {
+    spawn_blocking(|| ());
+}
+
+#[test]
+fn test_block_in_place_runs_inline() {
+    let rt = RuntimeBuilder::new().build();
+
+    let value = rt.block_on(async { block_in_place(|| 10) + 1 });
+
+    assert_eq!(value, 11);
+}
+
+#[test]
+fn test_block_in_place_from_spawned_task() {
+    let rt = RuntimeBuilder::new().build();
+
+    let value = rt.block_on(async { Task::spawn(async { block_in_place(|| 5) * 2 }).await });
+
+    assert_eq!(value, 10, "block_in_place should work on a worker thread");
+}
@@ -0,0 +1,116 @@
+use cadentis::task::{self, Builder};
+use cadentis::{RuntimeBuilder, Task};
+
+#[test]
+fn test_builder_spawn_named_task() {
+    let rt = RuntimeBuilder::new().build();
+
+    let value = rt.block_on(async { Builder::new().name("conn-handler").spawn(async { 7 }).await });
+
+    assert_eq!(value, 7);
+}
+
+#[test]
+fn test_join_handle_ids_are_unique() {
+    let rt = RuntimeBuilder::new().build();
+
+    let (a, b) = rt.block_on(async {
+        let a = Task::spawn(async {});
+        let b = Task::spawn(async {});
+        let ids = (a.id(), b.id());
+
+        a.await;
+        b.await;
+
+        ids
+    });
+
+    assert_ne!(a, b, "Each task should get its own ID");
+}
+
+#[test]
+fn test_task_id_matches_join_handle() {
+    let rt = RuntimeBuilder::new().build();
+
+    let (inner, outer) = rt.block_on(async {
+        let handle = Task::spawn(async { task::id() });
+        let outer = handle.id();
+
+        (handle.await, outer)
+    });
+
+    assert_eq!(inner, outer, "task::id() should match JoinHandle::id()");
+}
+
+#[test]
+fn test_try_id_outside_task() {
+    assert!(task::try_id().is_none());
+}
+
+#[test]
+#[should_panic(expected = "(conn-handler) panicked: boom")]
+fn test_panic_message_carries_task_name() {
+    let rt = RuntimeBuilder::new().build();
+
+    rt.block_on(async {
+        Builder::new()
+            .name("conn-handler")
+            .spawn(async { panic!("boom") })
+            .await
+    });
+}
+
+#[test]
+fn test_worker_survives_task_panic() {
+    let rt = RuntimeBuilder::new().build();
+
+    let value = rt.block_on(async {
+        let failed = Task::spawn(async {
+            panic!("boom");
+        });
+        drop(failed);
+
+        Task::spawn(async { 1 }).await
+    });
+
+    assert_eq!(value, 1, "Runtime should keep running after a task panics");
+}
+
+#[test]
+fn test_detached_panic_is_reported_with_task_name() {
+    const CHILD: &str = "CADENTIS_REPORT_PANIC_CHILD";
+
+    if std::env::var_os(CHILD).is_some() {
+        let rt = RuntimeBuilder::new().worker_threads(1).build();
+
+        let spawner = rt.handle().spawn(async {
+            let failed = Builder::new()
+                .name("conn-handler")
+                .spawn(async { panic!("boom") });
+            drop(failed);
+        });
+        rt.block_on(spawner);
+
+        // Give the worker time to poll the detached task, which nobody joins.
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        return;
+    }
+
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([
+            "test_detached_panic_is_reported_with_task_name",
+            "--exact",
+            "--nocapture",
+        ])
+        .env(CHILD, "1")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success());
+    assert!(
+        stderr.contains("(conn-handler) panicked: boom"),
+        "stderr was: {}",
+        stderr
+    );
+}
@@ -0,0 +1,83 @@
+use cadentis::{RuntimeBuilder, Task, task_local, yield_now};
+
+task_local! {
+    static REQUEST_ID: u64;
+    static TENANT: String;
+}
+
+#[test]
+fn test_task_local_scope_and_with() {
+    let rt = RuntimeBuilder::new().build();
+
+    let value = rt.block_on(REQUEST_ID.scope(7, async {
+        yield_now().await;
+        REQUEST_ID.with(|id| *id * 2)
+    }));
+
+    assert_eq!(value, 14, "Value should be visible inside its scope");
+}
+
+#[test]
+fn test_task_local_try_with_outside_scope() {
+    let rt = RuntimeBuilder::new().build();
+
+    let result = rt.block_on(async { REQUEST_ID.try_with(|id| *id) });
+
+    assert!(result.is_err(), "Value should not be set outside a scope");
+}
+
+#[test]
+fn test_task_local_nested_scopes_restore_outer_value() {
+    let rt = RuntimeBuilder::new().build();
+
+    let values = rt.block_on(REQUEST_ID.scope(1, async {
+        let inner = REQUEST_ID.scope(2, async { REQUEST_ID.get() }).await;
+        (inner, REQUEST_ID.get())
+    }));
+
+    assert_eq!(values, (2, 1));
+}
+
+#[test]
+fn test_task_local_survives_rescheduling_in_spawned_task() {
+    let rt = RuntimeBuilder::new().build();
+
+    let value = rt.block_on(async {
+        Task::spawn(TENANT.scope("acme".to_string(), async {
+            for _ in 0..10 {
+                yield_now().await;
+            }
+            TENANT.get()
+        }))
+        .await
+    });
+
+    assert_eq!(value, "acme");
+}
+
+#[test]
+fn test_task_local_not_inherited_by_default() {
+    let rt = RuntimeBuilder::new().build();
+
+    let inherited = rt.block_on(REQUEST_ID.scope(3, async {
+        Task::spawn(async { REQUEST_ID.try_with(|id| *id).is_ok() }).await
+    }));
+
+    assert!(!inherited, "Plain scopes should not leak into children");
+}
+
+#[test]
+fn test_task_local_scope_inherited_copies_into_children() {
+    let rt = RuntimeBuilder::new().build();
+
+    let (child, grandchild) = rt.block_on(TENANT.scope_inherited("acme".to_string(), async {
+        Task::spawn(async {
+            let grandchild = Task::spawn(async { TENANT.get() }).await;
+            (TENANT.get(), grandchild)
+        })
+        .await
+    }));
+
+    assert_eq!(child, "acme");
+    assert_eq!(grandchild, "acme");
+}
@@ -0,0 +1,135 @@
+use cadentis::task::{self, Scope};
+use cadentis::time::sleep;
+use cadentis::{RuntimeBuilder, Task};
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+struct DropFlag(Arc<AtomicBool>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_scope_children_borrow_from_parent() {
+    let rt = RuntimeBuilder::new().build();
+    let items = vec![1, 2, 3, 4];
+    let sum = AtomicUsize::new(0);
+
+    let (items_ref, sum_ref) = (&items, &sum);
+
+    let result: Result<(), ()> = rt.block_on(task::scope(|s| async move {
+        for item in items_ref {
+            s.spawn(async move {
+                task::yield_now().await;
+                sum_ref.fetch_add(*item, Ordering::SeqCst);
+                Ok(())
+            });
+        }
+        Ok(())
+    }));
+
+    assert!(result.is_ok());
+    assert_eq!(sum.load(Ordering::SeqCst), 10, "All children should finish");
+}
+
+#[test]
+fn test_scope_waits_for_children_after_body_returns() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+    let finished = Mutex::new(Vec::new());
+    let finished_ref = &finished;
+
+    let value = rt
+        .block_on(task::scope(|s: Scope<'_, ()>| async move {
+            s.spawn(async move {
+                sleep(Duration::from_millis(30)).await;
+                finished_ref.lock().unwrap().push("child");
+                Ok(())
+            });
+            Ok(5)
+        }))
+        .unwrap();
+
+    assert_eq!(value, 5);
+    assert_eq!(*finished.lock().unwrap(), vec!["child"]);
+}
+
+#[test]
+fn test_scope_first_error_cancels_siblings() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+    let dropped = Arc::new(AtomicBool::new(false));
+    let flag = DropFlag(dropped.clone());
+
+    let result = rt.block_on(task::scope(|s| async move {
+        s.spawn(async move {
+            let _flag = flag;
+            sleep(Duration::from_secs(60)).await;
+            Ok(())
+        });
+        s.spawn(async {
+            sleep(Duration::from_millis(10)).await;
+            Err("boom")
+        });
+        Ok(())
+    }));
+
+    assert_eq!(result, Err("boom"));
+    assert!(
+        dropped.load(Ordering::SeqCst),
+        "Sibling should be cancelled"
+    );
+}
+
+#[test]
+fn test_scope_body_error_cancels_children() {
+    let rt = RuntimeBuilder::new().build();
+    let dropped = Arc::new(AtomicBool::new(false));
+    let flag = DropFlag(dropped.clone());
+
+    let result: Result<(), &str> = rt.block_on(task::scope(|s| async move {
+        s.spawn(async move {
+            let _flag = flag;
+            std::future::pending::<()>().await;
+            Ok(())
+        });
+        task::yield_now().await;
+        Err("body failed")
+    }));
+
+    assert_eq!(result, Err("body failed"));
+    assert!(dropped.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_scope_inside_spawned_task() {
+    let rt = RuntimeBuilder::new().build();
+
+    let total = rt.block_on(async {
+        Task::spawn(async {
+            let data = vec![3usize; 8];
+            let sum = AtomicUsize::new(0);
+            let (data, sum_ref) = (&data, &sum);
+
+            task::scope(|s| async move {
+                for chunk in data.chunks(2) {
+                    s.spawn(async move {
+                        sum_ref.fetch_add(chunk.iter().sum(), Ordering::SeqCst);
+                        Ok::<_, ()>(())
+                    });
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+            sum.load(Ordering::SeqCst)
+        })
+        .await
+    });
+
+    assert_eq!(total, 24);
+}
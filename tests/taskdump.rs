@@ -0,0 +1,80 @@
+#![cfg(feature = "taskdump")]
+
+use cadentis::task::Builder;
+use cadentis::{RuntimeBuilder, Task, TaskState};
+use std::time::Duration;
+
+#[test]
+fn test_dump_lists_live_tasks_with_names_and_locations() {
+    let rt = RuntimeBuilder::new().build();
+    let handle = rt.handle().clone();
+
+    let dump = rt.block_on(async move {
+        let builder = Builder::new().name("stuck");
+        let stuck = builder.spawn(std::future::pending::<()>());
+        let line = line!() - 1;
+
+        while handle.dump().tasks().iter().all(|t| t.poll_count() == 0) {
+            cadentis::yield_now().await;
+        }
+
+        let dump = handle.dump();
+        stuck.abort();
+
+        (dump, line)
+    });
+
+    let (dump, line) = dump;
+    let task = &dump.tasks()[0];
+
+    assert_eq!(dump.tasks().len(), 1);
+    assert_eq!(task.name(), Some("stuck"));
+    assert_eq!(task.state(), TaskState::Idle);
+    assert_eq!(task.poll_count(), 1);
+    assert!(task.since_last_poll().is_some());
+    assert_eq!(task.location().file(), file!());
+    assert_eq!(task.location().line(), line);
+    assert!(dump.to_string().contains("(stuck) Idle polls=1"));
+}
+
+#[test]
+fn test_dump_excludes_completed_tasks() {
+    let rt = RuntimeBuilder::new().build();
+
+    rt.block_on(async {
+        Task::spawn(async {}).await;
+    });
+
+    assert!(rt.handle().dump().tasks().is_empty());
+}
+
+#[test]
+fn test_dump_reports_running_task() {
+    let rt = RuntimeBuilder::new().build();
+    let handle = rt.handle().clone();
+
+    let running = rt.block_on(async move {
+        let busy = Task::spawn(async {
+            std::thread::sleep(Duration::from_millis(200));
+        });
+
+        let mut running = false;
+        for _ in 0..100 {
+            if handle
+                .dump()
+                .tasks()
+                .iter()
+                .any(|t| t.state() == TaskState::Running)
+            {
+                running = true;
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        busy.await;
+        running
+    });
+
+    assert!(running, "A task being polled should be reported as running");
+}
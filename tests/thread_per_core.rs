@@ -0,0 +1,114 @@
+use cadentis::task::{spawn_on, yield_now};
+use cadentis::{RuntimeBuilder, Task};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, ThreadId};
+
+#[test]
+fn test_spawn_on_stays_on_worker() {
+    let rt = RuntimeBuilder::new().worker_threads(2).build();
+
+    rt.block_on(async {
+        for worker in 0..2 {
+            let threads: Vec<ThreadId> = spawn_on(worker, async {
+                let mut threads = Vec::new();
+
+                for _ in 0..10 {
+                    threads.push(thread::current().id());
+                    yield_now().await;
+                }
+
+                threads
+            })
+            .await;
+
+            assert!(threads.iter().all(|t| *t == threads[0]));
+            assert_ne!(threads[0], thread::current().id());
+        }
+    });
+}
+
+#[test]
+fn test_spawn_on_distinct_workers_use_distinct_threads() {
+    let rt = RuntimeBuilder::new().worker_threads(2).build();
+
+    let (first, second) = rt.block_on(async {
+        let first = spawn_on(0, async { thread::current().id() });
+        let second = spawn_on(1, async { thread::current().id() });
+
+        (first.await, second.await)
+    });
+
+    assert_ne!(first, second);
+}
+
+#[test]
+fn test_thread_per_core_keeps_spawns_local() {
+    let rt = RuntimeBuilder::new().thread_per_core([0, 1]).build();
+
+    rt.block_on(async {
+        assert_eq!(cadentis::Handle::current().metrics().num_workers(), 2);
+
+        let (parent, children) = spawn_on(1, async {
+            let parent = thread::current().id();
+            let mut children = Vec::new();
+
+            for _ in 0..8 {
+                let child = Task::spawn(async {
+                    yield_now().await;
+                    thread::current().id()
+                });
+
+                children.push(child.await);
+            }
+
+            (parent, children)
+        })
+        .await;
+
+        assert!(children.iter().all(|child| *child == parent));
+    });
+}
+
+#[test]
+#[should_panic(expected = "worker index 4 out of range (2 workers)")]
+fn test_spawn_on_rejects_unknown_worker() {
+    let rt = RuntimeBuilder::new().worker_threads(2).build();
+
+    rt.block_on(async {
+        spawn_on(4, async {});
+    });
+}
+
+#[test]
+fn test_yielding_homed_task_lets_its_siblings_run() {
+    let rt = RuntimeBuilder::new().thread_per_core([0]).build();
+
+    let yields = rt.block_on(async {
+        let ran = Arc::new(AtomicBool::new(false));
+
+        let sibling = spawn_on(0, {
+            let ran = ran.clone();
+            async move { ran.store(true, Ordering::SeqCst) }
+        });
+
+        // Yields until the sibling homed on the same worker has run.
+        let yielder = spawn_on(0, async move {
+            let mut yields = 0;
+
+            while !ran.load(Ordering::SeqCst) && yields < 100_000 {
+                yields += 1;
+                yield_now().await;
+            }
+
+            yields
+        });
+
+        let yields = yielder.await;
+        sibling.await;
+
+        yields
+    });
+
+    assert!(yields < 100, "sibling ran only after {yields} yields");
+}
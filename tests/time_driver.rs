@@ -0,0 +1,184 @@
+use cadentis::time::{Instant, sleep, timeout};
+use cadentis::tools::retry;
+use cadentis::{RuntimeBuilder, Task, yield_now};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+#[test]
+fn test_sleep_without_io() {
+    let rt = RuntimeBuilder::new().enable_time().build();
+
+    assert!(rt.time_enabled());
+    assert!(!rt.io_enabled());
+
+    let elapsed = rt.block_on(async {
+        let start = Instant::now();
+        sleep(Duration::from_millis(20)).await;
+        start.elapsed()
+    });
+
+    assert!(elapsed >= Duration::from_millis(20));
+}
+
+#[test]
+fn test_sleep_in_tasks_without_io() {
+    let rt = RuntimeBuilder::new()
+        .enable_time()
+        .worker_threads(2)
+        .build();
+
+    let total = rt.block_on(async {
+        let tasks: Vec<_> = (1..=10)
+            .map(|i| {
+                Task::spawn(async move {
+                    sleep(Duration::from_millis(i * 3)).await;
+                    i
+                })
+            })
+            .collect();
+
+        let mut total = 0;
+        for task in tasks {
+            total += task.await;
+        }
+        total
+    });
+
+    assert_eq!(total, 55);
+}
+
+#[test]
+fn test_sleep_fires_while_the_worker_stays_busy() {
+    let rt = RuntimeBuilder::new()
+        .enable_time()
+        .worker_threads(1)
+        .build();
+    let stop = Arc::new(AtomicBool::new(false));
+    let fired = Arc::new(AtomicBool::new(false));
+
+    // Spawned from outside, so no `block_on` thread turns the driver.
+    let spinner = rt.handle().spawn({
+        let stop = stop.clone();
+        async move {
+            while !stop.load(Ordering::Relaxed) {
+                yield_now().await;
+            }
+        }
+    });
+    rt.handle().spawn({
+        let fired = fired.clone();
+        async move {
+            sleep(Duration::from_millis(20)).await;
+            fired.store(true, Ordering::Relaxed);
+        }
+    });
+
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    while !fired.load(Ordering::Relaxed) && std::time::Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(5));
+    }
+
+    let fired_while_busy = fired.load(Ordering::Relaxed);
+    stop.store(true, Ordering::Relaxed);
+    rt.block_on(spinner);
+
+    assert!(fired_while_busy);
+}
+
+#[test]
+fn test_timeout_without_io() {
+    let rt = RuntimeBuilder::new().enable_time().build();
+
+    rt.block_on(async {
+        let timed_out = timeout(Duration::from_millis(10), sleep(Duration::from_secs(60))).await;
+        assert!(timed_out.is_err());
+
+        let finished = timeout(Duration::from_secs(60), async { 7 }).await;
+        assert_eq!(finished, Ok(7));
+    });
+}
+
+#[test]
+fn test_retry_with_interval_without_io() {
+    let rt = RuntimeBuilder::new().enable_time().build();
+    let attempts = Arc::new(AtomicUsize::new(0));
+
+    let result = rt.block_on(async {
+        retry(3, || {
+            let attempts = attempts.clone();
+            Task::spawn(async move {
+                let n = attempts.fetch_add(1, Ordering::SeqCst);
+                if n < 2 { Err("fail") } else { Ok(n) }
+            })
+        })
+        .set_interval(Duration::from_millis(5))
+        .await
+    });
+
+    assert_eq!(result, Ok(2));
+}
+
+#[test]
+fn test_start_paused_without_io() {
+    let rt = RuntimeBuilder::new()
+        .enable_time()
+        .start_paused(true)
+        .build();
+
+    let (virtual_elapsed, wall) = rt.block_on(async {
+        let start = Instant::now();
+        let wall = std::time::Instant::now();
+
+        sleep(Duration::from_secs(3600)).await;
+
+        (start.elapsed(), wall.elapsed())
+    });
+
+    assert!(virtual_elapsed >= Duration::from_secs(3600));
+    assert!(wall < Duration::from_secs(5));
+}
+
+#[test]
+fn test_dropped_sleep_cancels_its_timer() {
+    let rt = RuntimeBuilder::new().enable_time().build();
+    let handle = rt.handle().clone();
+
+    rt.block_on(async move {
+        let timed_out = timeout(Duration::from_millis(5), sleep(Duration::from_secs(60))).await;
+
+        assert!(timed_out.is_err());
+        assert_eq!(handle.metrics().pending_timer_count(), 0);
+    });
+}
+
+#[test]
+fn test_sleep_without_time_panics() {
+    let rt = RuntimeBuilder::new().build();
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        rt.block_on(async { sleep(Duration::from_millis(1)).await })
+    }));
+
+    let payload = result.unwrap_err();
+    let message = payload
+        .downcast_ref::<String>()
+        .cloned()
+        .unwrap_or_default();
+
+    assert!(message.contains("RuntimeBuilder::enable_time()"));
+}
+
+#[test]
+fn test_enable_all() {
+    let rt = RuntimeBuilder::new().enable_all().build();
+
+    assert!(rt.io_enabled());
+    assert!(rt.fs_enabled());
+    assert!(rt.time_enabled());
+
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    assert!(rt.time_enabled());
+}
@@ -0,0 +1,133 @@
+use cadentis::time::{self, Instant, sleep, timeout};
+use cadentis::tools::retry;
+use cadentis::{RuntimeBuilder, Task, yield_now};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+#[test]
+fn test_start_paused_auto_advances_sleep() {
+    let rt = RuntimeBuilder::new().enable_io().start_paused(true).build();
+    let real = std::time::Instant::now();
+
+    let elapsed = rt.block_on(async {
+        let start = Instant::now();
+        sleep(Duration::from_secs(3600)).await;
+        start.elapsed()
+    });
+
+    assert_eq!(elapsed, Duration::from_secs(3600));
+    assert!(real.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_advance_fires_due_timers_only() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+    let started = Arc::new(AtomicBool::new(false));
+    let fired = Arc::new(AtomicBool::new(false));
+    let (started_clone, fired_clone) = (started.clone(), fired.clone());
+    let fired_check = fired.clone();
+
+    let elapsed = rt.block_on(async move {
+        time::pause();
+        let start = Instant::now();
+
+        let handle = Task::spawn(async move {
+            started_clone.store(true, Ordering::SeqCst);
+            sleep(Duration::from_secs(10)).await;
+            fired_clone.store(true, Ordering::SeqCst);
+        });
+
+        while !started.load(Ordering::SeqCst) {
+            yield_now().await;
+        }
+
+        time::advance(Duration::from_secs(5)).await;
+        assert!(
+            !fired_check.load(Ordering::SeqCst),
+            "Timer should not fire early"
+        );
+
+        time::advance(Duration::from_secs(5)).await;
+        handle.await;
+
+        start.elapsed()
+    });
+
+    assert!(fired.load(Ordering::SeqCst));
+    assert_eq!(elapsed, Duration::from_secs(10));
+}
+
+#[test]
+fn test_paused_timeout_expires_without_waiting() {
+    let rt = RuntimeBuilder::new().enable_io().start_paused(true).build();
+    let real = std::time::Instant::now();
+
+    let result =
+        rt.block_on(async { timeout(Duration::from_secs(30), std::future::pending::<()>()).await });
+
+    assert!(result.is_err());
+    assert!(real.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_paused_retry_interval_is_instant() {
+    let rt = RuntimeBuilder::new().enable_io().start_paused(true).build();
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let attempts_clone = attempts.clone();
+
+    let (result, elapsed) = rt.block_on(async move {
+        let start = Instant::now();
+        let result = retry(3, move || {
+            let attempts = attempts_clone.clone();
+            async move {
+                if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err("fail")
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .set_interval(Duration::from_secs(60))
+        .await;
+
+        (result, start.elapsed())
+    });
+
+    assert!(result.is_ok());
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    assert!(elapsed >= Duration::from_secs(120));
+}
+
+#[test]
+fn test_resume_continues_from_paused_time() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    let elapsed = rt.block_on(async {
+        let start = Instant::now();
+
+        time::pause();
+        time::advance(Duration::from_secs(3600)).await;
+        time::resume();
+
+        sleep(Duration::from_millis(20)).await;
+        start.elapsed()
+    });
+
+    assert!(elapsed >= Duration::from_secs(3600) + Duration::from_millis(20));
+    assert!(elapsed < Duration::from_secs(3605));
+}
+
+#[test]
+#[should_panic(expected = "requires a paused clock")]
+fn test_advance_requires_paused_clock() {
+    let rt = RuntimeBuilder::new().enable_io().build();
+
+    rt.block_on(time::advance(Duration::from_secs(1)));
+}
+
+#[test]
+#[should_panic(expected = "time::pause() called outside of a runtime context")]
+fn test_pause_outside_runtime_panics() {
+    time::pause();
+}
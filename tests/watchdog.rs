@@ -0,0 +1,94 @@
+use cadentis::{RuntimeBuilder, Stall, StallKind, Task};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Duration;
+
+fn watched_runtime(threshold: Duration, stalls: &Arc<Mutex<Vec<Stall>>>) -> cadentis::Runtime {
+    let stalls = stalls.clone();
+
+    RuntimeBuilder::new()
+        .watchdog(threshold, move |stall| {
+            stalls.lock().unwrap().push(stall.clone())
+        })
+        .build()
+}
+
+#[test]
+fn test_watchdog_reports_slow_poll() {
+    let stalls = Arc::new(Mutex::new(Vec::new()));
+    let rt = watched_runtime(Duration::from_millis(20), &stalls);
+
+    let id = rt.block_on(async {
+        let handle = cadentis::task::Builder::new().name("busy").spawn(async {
+            std::thread::sleep(Duration::from_millis(50));
+        });
+        let id = handle.id();
+        handle.await;
+        id
+    });
+
+    let stalls = stalls.lock().unwrap();
+    let stall = stalls
+        .iter()
+        .find(|s| s.kind() == StallKind::SlowPoll)
+        .expect("slow poll was not reported");
+
+    assert_eq!(stall.task_id(), id);
+    assert_eq!(stall.task_name(), Some("busy"));
+    assert!(stall.duration() >= Duration::from_millis(50));
+    assert_eq!(stall.location().file(), file!());
+    assert!(stall.to_string().contains("(busy) was polled for"));
+}
+
+#[test]
+fn test_watchdog_reports_stuck_worker_while_polling() {
+    let stalls = Arc::new(Mutex::new(Vec::new()));
+    let rt = watched_runtime(Duration::from_millis(20), &stalls);
+    let seen = stalls.clone();
+    let (tx, rx) = mpsc::channel();
+
+    // Spawned from outside `block_on` so that a worker, not this thread, polls it.
+    rt.spawn(async move {
+        for _ in 0..100 {
+            let stuck = seen
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|s| matches!(s.kind(), StallKind::StuckWorker { .. }));
+
+            if stuck {
+                break;
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        tx.send(seen.lock().unwrap().clone()).unwrap();
+    });
+
+    let stalls = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    let stuck: Vec<_> = stalls
+        .iter()
+        .filter(|s| matches!(s.kind(), StallKind::StuckWorker { .. }))
+        .collect();
+
+    assert_eq!(stuck.len(), 1, "a stuck poll is reported once");
+    assert!(stuck[0].duration() >= Duration::from_millis(20));
+    assert!(stuck[0].to_string().contains("has blocked worker"));
+}
+
+#[test]
+fn test_watchdog_ignores_fast_polls() {
+    let stalls = Arc::new(Mutex::new(Vec::new()));
+    let rt = watched_runtime(Duration::from_secs(1), &stalls);
+
+    rt.block_on(async {
+        for _ in 0..10 {
+            Task::spawn(async {
+                cadentis::yield_now().await;
+            })
+            .await;
+        }
+    });
+
+    assert!(stalls.lock().unwrap().is_empty());
+}